@@ -0,0 +1,444 @@
+use std::error::Error;
+use crate::wordle::*;
+
+const USAGE: &str = "Usage: wordle_solve guess [options] [WORD:FEEDBACK ...]
+       wordle_solve simulate [options] [--games <count>]
+       wordle_solve build-tree [options] [--output <file>]
+       wordle_solve verify-tree [options]
+       wordle_solve openers [options] [--top <count>]
+       wordle_solve absurdle [options] [WORD:FEEDBACK ...]
+       wordle_solve analyse [options] [--output <file>] WORD:FEEDBACK ...
+       wordle_solve infer [options] --grids <file> [--top <count>]
+       wordle_solve reverse [options] --answer <word> --grids <file> [--top <count>]
+
+Feedback has one character per letter, g for green, y for yellow and . for absent, e.g. crane:..y.g,
+and the rows can also be given as one argument such as 'crane:..y.g slate:gg..y', the way the app
+copies the board with Copy Board.
+
+Options:
+  --dictionary <url or file>         word list to guess from
+  --answers <url or file>            words that can be the answer, the whole dictionary by default
+  --past-answers <file>              list of previous answers, one per line, optionally dated
+  --past-answers-mode <mode>         off, exclude or downweight
+  --priors <file>                    word frequency list, a word and a count or probability per line
+  --strategy <strategy>              frequency, likely, expected, minimax or lookahead, minimax also
+                                     reports whether a solve is guaranteed
+  --lookahead-breadth <count>        how many guesses lookahead looks two steps ahead for, defaults to 10
+  --lookahead-time <seconds>         how long lookahead may take before using the best so far
+  --endgame <count>                  search every guess exactly at this many candidates or fewer,
+                                     0 turns it off, defaults to 50
+  --endgame-goal <goal>              guesses to solve in the fewest guesses on average, or solves
+                                     to solve as often as possible in the attempts left
+  --opener <words>                   one word, or two separated by a comma, to always open with,
+                                     the pinned opener by default
+  --grids <file>                     share blocks for infer and reverse, each starting with its
+                                     Wordle header
+  --answer <word>                    the answer the share blocks for reverse were played against
+  --tree <file>                      decision tree to guess from while the board follows it, the
+                                     saved tree by default
+
+Simulate plays every answer and reports how many guesses the strategy took, --games only plays
+the first few answers. Build-tree works out the strategy's guess for every feedback and saves the
+tree, to the saved tree unless --output is given. Verify-tree checks the tree solves every answer.
+Openers ranks every first guess with the strategy, showing the best 20 unless --top is given, and
+saves the ranking so later first guesses with the same dictionary and strategy are instant.
+Absurdle plays against a host that always keeps the most words in play and shows the shortest
+sequence of guesses found that forces a win, with the feedback the host gives each one. Analyse
+grades every row of a game against the strategy and prints the report as Markdown, or saves it to
+--output. Infer reads share blocks pasted one after another, without the guesses, and ranks the
+answers that fit all of them by how likely each is. Reverse lists the words that could have been
+played in each row of the share blocks given the answer, narrowed to the ones that fit a hard mode
+game when the grid could have been one.";
+
+#[derive(Debug)]
+struct Options {
+    dictionary: String,
+    past_answers: Option<String>,
+    past_answer_mode: PastAnswerMode,
+    priors: Option<String>,
+    strategy: StrategyKind,
+    endgame_threshold: usize,
+    endgame_goal: EndgameGoal,
+    lookahead: Lookahead,
+    answers: Option<String>,
+    games: Option<usize>,
+    tree: Option<String>,
+    output: Option<String>,
+    opener: Option<Opener>,
+    top: usize,
+    grids: Option<String>,
+    answer: Option<String>,
+    board: Board,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            dictionary: DEFAULT_DICTIONARY_URL.to_string(),
+            past_answers: None,
+            past_answer_mode: PastAnswerMode::Off,
+            priors: None,
+            strategy: StrategyKind::default(),
+            endgame_threshold: DEFAULT_ENDGAME_THRESHOLD,
+            endgame_goal: EndgameGoal::default(),
+            lookahead: Lookahead::default(),
+            answers: None,
+            games: None,
+            tree: None,
+            output: None,
+            opener: None,
+            top: 20,
+            grids: None,
+            answer: None,
+            board: Board::default(),
+        }
+    }
+}
+
+// run the command line interface, returning the process exit code
+pub fn run(args: &[String]) -> i32 {
+    match execute(args) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("{}", USAGE);
+            1
+        }
+    }
+}
+
+fn execute(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let Some((command, args)) = args.split_first() else {
+        return Err("no command given".into());
+    };
+    match command.as_str() {
+        "guess" => guess(&parse_options(args)?),
+        "simulate" => simulate(&parse_options(args)?),
+        "build-tree" => build_tree(&parse_options(args)?),
+        "verify-tree" => verify_tree(&parse_options(args)?),
+        "openers" => openers(&parse_options(args)?),
+        "absurdle" => absurdle(&parse_options(args)?),
+        "analyse" => analyse(&parse_options(args)?),
+        "infer" => infer(&parse_options(args)?),
+        "reverse" => reverse(&parse_options(args)?),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("unknown command '{}'", command).into()),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, Box<dyn Error>> {
+    let mut options = Options::default();
+    let mut rows: Vec<&str> = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dictionary" => options.dictionary = value(arg, args.next())?,
+            "--past-answers" => options.past_answers = Some(value(arg, args.next())?),
+            "--past-answers-mode" => {
+                let mode = value(arg, args.next())?;
+                options.past_answer_mode = PastAnswerMode::parse(&mode)
+                    .ok_or(format!("unknown past answers mode '{}'", mode))?;
+            }
+            "--priors" => options.priors = Some(value(arg, args.next())?),
+            "--strategy" => {
+                let strategy = value(arg, args.next())?;
+                options.strategy = StrategyKind::parse(&strategy)
+                    .ok_or(format!("unknown strategy '{}'", strategy))?;
+            }
+            "--endgame" => options.endgame_threshold = number(arg, args.next())?,
+            "--lookahead-breadth" => options.lookahead.breadth = number(arg, args.next())?,
+            "--lookahead-time" => {
+                let seconds = value(arg, args.next())?;
+                let seconds: f64 = seconds.parse().map_err(|_| format!("'{}' is not a number of seconds", seconds))?;
+                options.lookahead.time_limit = std::time::Duration::try_from_secs_f64(seconds)
+                    .map_err(|_| format!("'{}' is not a number of seconds", seconds))?;
+            }
+            "--answers" => options.answers = Some(value(arg, args.next())?),
+            "--games" => options.games = Some(number(arg, args.next())?),
+            "--tree" => options.tree = Some(value(arg, args.next())?),
+            "--output" => options.output = Some(value(arg, args.next())?),
+            "--opener" => options.opener = Some(Opener::parse(&value(arg, args.next())?)?),
+            "--top" => options.top = number(arg, args.next())?,
+            "--grids" => options.grids = Some(value(arg, args.next())?),
+            "--answer" => options.answer = Some(value(arg, args.next())?.to_lowercase()),
+            "--endgame-goal" => {
+                let goal = value(arg, args.next())?;
+                options.endgame_goal = EndgameGoal::parse(&goal)
+                    .ok_or(format!("unknown endgame goal '{}'", goal))?;
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{}'", arg).into()),
+            _ => rows.push(arg),
+        }
+    }
+    // the rows read as one board, so an error's column counts from the first row
+    let rows = rows.join(" ");
+    options.board = Notation::parse(&rows).map_err(|e| format!("in '{}' at {}", rows, e))?;
+    Ok(options)
+}
+
+fn value(option: &str, value: Option<&String>) -> Result<String, Box<dyn Error>> {
+    value.cloned().ok_or(format!("{} needs a value", option).into())
+}
+
+fn number(option: &str, arg: Option<&String>) -> Result<usize, Box<dyn Error>> {
+    let value = value(option, arg)?;
+    value.parse().map_err(|_| format!("{} needs a whole number, not '{}'", option, value).into())
+}
+
+// load the dictionary along with everything saved by the gui
+fn statistics(options: &Options) -> Result<Statistics, Box<dyn Error>> {
+    let mut statistics = Statistics::default();
+    let skipped = statistics.add_words(&load_source(&options.dictionary)?);
+    if skipped > 0 {
+        eprintln!("{} lines skipped, only words of a to z are used", skipped);
+    }
+    if let Some(source) = &options.answers {
+        let answers: Vec<String> = load_source(source)?.lines()
+            .map(|line| line.trim().to_lowercase())
+            .filter(|line| !line.is_empty())
+            .collect();
+        statistics.set_answers(&answers);
+    }
+    // the matrix only speeds things up, so a data directory that can't be written to is no reason to stop
+    if let Err(e) = statistics.load_patterns() {
+        eprintln!("Error: {}", e);
+    }
+    statistics.set_blocklist(Blocklist::load(&Blocklist::path())?);
+    let past_answers = match &options.past_answers {
+        Some(path) => PastAnswers::load(&std::path::PathBuf::from(path))?,
+        None => PastAnswers::load(&PastAnswers::path()).unwrap_or_default(),
+    };
+    statistics.set_past_answers(past_answers);
+    statistics.set_past_answer_mode(options.past_answer_mode);
+    let priors = match &options.priors {
+        Some(path) => Priors::load(&std::path::PathBuf::from(path))?,
+        None => Priors::load(&Priors::path()).unwrap_or_default(),
+    };
+    statistics.set_priors(priors);
+    statistics.set_strategy(options.strategy);
+    statistics.set_endgame_threshold(options.endgame_threshold);
+    statistics.set_endgame_goal(options.endgame_goal);
+    statistics.set_lookahead(options.lookahead);
+    let tree = match &options.tree {
+        Some(path) => DecisionTree::load(&std::path::PathBuf::from(path))?,
+        None => DecisionTree::load(&DecisionTree::path()).unwrap_or_default(),
+    };
+    statistics.set_tree(tree);
+    let opener = match &options.opener {
+        Some(opener) => opener.clone(),
+        None => Opener::load(&Opener::path()).unwrap_or_default(),
+    };
+    for word in statistics.set_opener(opener) {
+        eprintln!("{} isn't in the dictionary, so it's never played", word);
+    }
+    statistics.set_openers(Openers::load(&Openers::path(&statistics.openers_key())).unwrap_or_default());
+    statistics.set_board(&options.board);
+    Ok(statistics)
+}
+
+fn guess(options: &Options) -> Result<(), Box<dyn Error>> {
+    let mut statistics = statistics(options)?;
+    println!("Candidates: {}", statistics.candidate_count());
+    // the same order the gui goes through, a pinned opener, the tree and the cached openers before
+    // the endgame and the strategy
+    let guess = statistics.guess();
+    if guess.is_empty() {
+        println!("No valid guesses left");
+    } else {
+        println!("Guess: {}", guess);
+    }
+    if options.strategy == StrategyKind::Minimax {
+        println!("{}", statistics.guarantee());
+    }
+    Ok(())
+}
+
+fn simulate(options: &Options) -> Result<(), Box<dyn Error>> {
+    let mut statistics = statistics(options)?;
+    let mut answers = statistics.candidates();
+    if let Some(games) = options.games {
+        answers.truncate(games);
+    }
+    print!("{}", Simulation::run(&mut statistics, &answers));
+    Ok(())
+}
+
+fn build_tree(options: &Options) -> Result<(), Box<dyn Error>> {
+    let statistics = statistics(options)?;
+    let tree = DecisionTree::build(&statistics);
+    let path = options.output.as_ref().map_or(DecisionTree::path(), std::path::PathBuf::from);
+    tree.save(&path)?;
+    println!("Saved to {}", path.display());
+    println!("{}", tree.verify(&statistics.candidates(), &statistics.guesses()));
+    Ok(())
+}
+
+fn verify_tree(options: &Options) -> Result<(), Box<dyn Error>> {
+    let statistics = statistics(options)?;
+    if statistics.tree().is_empty() {
+        return Err("no decision tree to verify".into());
+    }
+    println!("{}", statistics.tree().verify(&statistics.candidates(), &statistics.guesses()));
+    Ok(())
+}
+
+fn openers(options: &Options) -> Result<(), Box<dyn Error>> {
+    let statistics = statistics(options)?;
+    let key = statistics.openers_key();
+    let openers = match Openers::load(&Openers::path(&key)) {
+        Ok(openers) if openers.is_for(&key) => openers,
+        _ => {
+            let openers = statistics.analyse_openers();
+            openers.save()?;
+            openers
+        }
+    };
+    for (idx, (score, word)) in openers.ranked().iter().take(options.top).enumerate() {
+        println!("{:>4}. {} {:.4}", idx + 1, word, score);
+    }
+    Ok(())
+}
+
+fn absurdle(options: &Options) -> Result<(), Box<dyn Error>> {
+    let statistics = statistics(options)?;
+    println!("Candidates: {}", statistics.candidate_count());
+    match Absurdle::solve(&statistics.position()) {
+        Some(steps) => {
+            for (idx, step) in steps.iter().enumerate() {
+                println!("{:>2}. {}", idx + 1, step);
+            }
+            println!("Forced win in {} guesses", steps.len());
+        }
+        None => println!("No forced win found"),
+    }
+    Ok(())
+}
+
+fn analyse(options: &Options) -> Result<(), Box<dyn Error>> {
+    let mut statistics = statistics(options)?;
+    let analysis = statistics.analyse_with(&options.board, &Progress::default());
+    if analysis.rows.is_empty() {
+        return Err("no rows of feedback to analyse".into());
+    }
+    match &options.output {
+        Some(path) => {
+            analysis.save(&std::path::PathBuf::from(path))?;
+            println!("Saved to {}", path);
+        }
+        None => print!("{}", analysis.markdown()),
+    }
+    Ok(())
+}
+
+fn load_grids(command: &str, options: &Options) -> Result<Vec<ShareGrid>, Box<dyn Error>> {
+    let Some(path) = &options.grids else {
+        return Err(format!("{} needs --grids", command).into());
+    };
+    Ok(ShareGrid::parse_all(&std::fs::read_to_string(path)?)?)
+}
+
+fn infer(options: &Options) -> Result<(), Box<dyn Error>> {
+    let grids = load_grids("infer", options)?;
+    let statistics = statistics(options)?;
+    let ranked = statistics.infer_with(&grids, &Progress::default());
+    println!("{} answers fit {} grids", ranked.len(), grids.len());
+    for (idx, (likelihood, word)) in ranked.iter().take(options.top).enumerate() {
+        println!("{:>4}. {} {:.1}%", idx + 1, word, likelihood * 100.0);
+    }
+    Ok(())
+}
+
+fn reverse(options: &Options) -> Result<(), Box<dyn Error>> {
+    let Some(answer) = &options.answer else {
+        return Err("reverse needs --answer".into());
+    };
+    let grids = load_grids("reverse", options)?;
+    let statistics = statistics(options)?;
+    for (idx, grid) in grids.iter().enumerate() {
+        println!("Grid {}", idx + 1);
+        for (row_idx, row) in statistics.reverse(answer, grid).iter().enumerate() {
+            let (words, mode) = if row.hard_words.is_empty() {
+                (&row.words, "")
+            } else {
+                (&row.hard_words, " in hard mode")
+            };
+            let shown: Vec<&str> = words.iter().take(options.top).map(|word| word.as_str()).collect();
+            let more = if words.len() > shown.len() { ", ..." } else { "" };
+            println!("{:>4}. {} {} words{}: {}{}", row_idx + 1, row.pattern, words.len(), mode, shown.join(", "), more);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_options() {
+        let result = super::parse_options(&args(&["--dictionary", "words.txt", "--past-answers-mode", "exclude", "--strategy", "expected", "crane:..y.g"])).unwrap();
+        assert_eq!(result.dictionary, "words.txt");
+        assert_eq!(result.strategy, StrategyKind::MinExpectedGuesses);
+        assert_eq!(result.past_answer_mode, PastAnswerMode::Exclude);
+        assert_eq!(result.board.words[0].letters[2].get_state(), LetterState::Present);
+        assert_eq!(result.board.words[0].letters[4].get_state(), LetterState::Correct);
+        assert_eq!(result.board.words[1].letters[0].get_state(), LetterState::Disabled);
+        assert_eq!(result.endgame_threshold, DEFAULT_ENDGAME_THRESHOLD);
+
+        let result = super::parse_options(&args(&["--endgame", "20", "--endgame-goal", "solves"])).unwrap();
+        assert_eq!(result.endgame_threshold, 20);
+        assert_eq!(result.endgame_goal, EndgameGoal::MostSolves);
+
+        let result = super::parse_options(&args(&["--lookahead-breadth", "4", "--lookahead-time", "0.5", "--games", "10"])).unwrap();
+        assert_eq!(result.lookahead.breadth, 4);
+        assert_eq!(result.lookahead.time_limit, std::time::Duration::from_millis(500));
+        assert_eq!(result.games, Some(10));
+
+        let result = super::parse_options(&args(&["--answers", "answers.txt", "--tree", "tree.txt", "--output", "out.txt"])).unwrap();
+        assert_eq!(result.answers, Some(String::from("answers.txt")));
+        assert_eq!(result.tree, Some(String::from("tree.txt")));
+        assert_eq!(result.output, Some(String::from("out.txt")));
+
+        let result = super::parse_options(&args(&["--grids", "grids.txt", "--answer", "Slate"])).unwrap();
+        assert_eq!(result.grids, Some(String::from("grids.txt")));
+        assert_eq!(result.answer, Some(String::from("slate")));
+
+        let result = super::parse_options(&args(&["--opener", "crane,slate", "--top", "5"])).unwrap();
+        assert_eq!(result.opener.unwrap().words(), ["crane", "slate"]);
+        assert_eq!(result.top, 5);
+    }
+
+    #[test]
+    fn parse_options_errors() {
+        assert!(super::parse_options(&args(&["--dictionary"])).is_err());
+        assert!(super::parse_options(&args(&["--past-answers-mode", "sometimes"])).is_err());
+        assert!(super::parse_options(&args(&["--verbose"])).is_err());
+        assert!(super::parse_options(&args(&["--strategy", "psychic"])).is_err());
+        assert!(super::parse_options(&args(&["--endgame", "lots"])).is_err());
+        assert!(super::parse_options(&args(&["--endgame-goal", "style"])).is_err());
+        assert!(super::parse_options(&args(&["--lookahead-time", "-1"])).is_err());
+        assert!(super::parse_options(&args(&["--games", "all"])).is_err());
+        assert!(super::parse_options(&args(&["--opener", "cranes"])).is_err());
+        assert!(super::parse_options(&args(&["crane"])).is_err());
+        assert!(super::parse_options(&args(&["crane:..x.g"])).is_err());
+        assert!(super::parse_options(&args(&["crane:..g"])).is_err());
+        let result = super::parse_options(&args(&["crane:..y.g", "slate:gg.x."])).unwrap_err();
+        assert_eq!(result.to_string(), "in 'crane:..y.g slate:gg.x.' at column 22: unknown feedback 'x', expected g, y or .");
+        let result = super::parse_options(&args(&["crane:..y.g slate:gg..y"])).unwrap();
+        assert_eq!(Notation::print(&result.board), "crane:..y.g slate:gg..y");
+    }
+
+    #[test]
+    fn execute() {
+        assert!(super::execute(&args(&[])).is_err());
+        assert!(super::execute(&args(&["solve"])).is_err());
+        assert!(super::execute(&args(&["help"])).is_ok());
+    }
+}
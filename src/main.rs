@@ -4,32 +4,48 @@
 )] // hide console window on Windows in release
 #![allow(rustdoc::missing_crate_level_docs)]
 
-use std::error::Error;
 use eframe::{egui};
-use reqwest;
 
 mod wordle;
+mod cli;
 
 use wordle::*;
 
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([640.0, 480.0]).with_resizable(false),
+        viewport: egui::ViewportBuilder::default().with_inner_size([960.0, 600.0]).with_resizable(false),
 
         ..Default::default()
     };
 
-    let app = WordleSolve::default();
-
     eframe::run_native(
         "Wordle Solver",
         options,
         Box::new(|_cc| {
+            // picked back up once the window is open, the dictionary arrives on its own thread
+            let mut app = WordleSolve::default();
+            app.load_blocklist();
+            if Session::path().exists() {
+                app.load_session(&Session::path());
+            }
+            app.load_lists();
+            if Opener::path().exists() {
+                app.load_opener();
+            }
             Box::<WordleSolve>::new(app)
         }),
     )
 }
 
+// what update_analysis works out about the board, a correction for it, whether it can be solved in
+// time and the best endgame move
+type BoardChecks = (Option<Correction>, Option<Guarantee>, Option<EndgameMove>);
+
 #[derive(Debug, Clone)]
 struct WordleSolve {
     words_url: String,
@@ -37,46 +53,1386 @@ struct WordleSolve {
     guess: String,
     guess_num: usize,
     statistics: Statistics,
+    candidate_search: String,
+    // the candidates matching the search, how many candidates there are and the conflicts between
+    // the rows, kept from frame to frame until the board, the words or the search change
+    candidates_key: String,
+    shown_candidates: Vec<String>,
+    candidate_count: usize,
+    conflicts: Vec<Conflict>,
+    // counts every dictionary or past answer list loaded, the candidates change with either
+    words_version: usize,
+    // the board the correction and guarantee were last worked out for, both mean re-filtering the
+    // dictionary many times
+    analysis_key: String,
+    correction: Option<Correction>,
+    guarantee: Option<Guarantee>,
+    endgame: Option<EndgameMove>,
+    // the correction, guarantee and endgame being worked out on another thread
+    pending_checks: Option<Task<BoardChecks>>,
+    // the guess being worked out on another thread and the row it goes in
+    pending_guess: Option<(usize, Task<String>)>,
+    // the dictionary being downloaded on another thread, and whether the board is kept when it
+    // arrives, as it is when a session is picked back up
+    pending_download: Option<(bool, Task<Result<String, String>>)>,
+    // why the last download failed, or the lines it had to skip
+    download_status: String,
+    // the pattern matrix for a newly downloaded dictionary, mapped or built on another thread
+    pending_patterns: Option<Task<(PatternMatrix, std::io::Result<()>)>>,
+    // why the last pattern matrix couldn't be saved for next time
+    patterns_status: String,
+    // several boards played at once, only the single board is played without it
+    multi: Option<MultiBoard>,
+    // the boards the candidate counts were last worked out for, and the counts
+    multi_key: String,
+    multi_counts: Vec<usize>,
+    // the app hosting a game for the user to play, instead of solving
+    host: Option<HostGame>,
+    host_guess: String,
+    // picks the secret word of a practice game, today's date when blank
+    host_seed: String,
+    // why the last guess wasn't accepted
+    host_status: String,
+    // how the solver would have played each row of the hosted game, worked out in order on other
+    // threads
+    host_commentary: Vec<Commentary>,
+    pending_commentary: Vec<Task<Commentary>>,
+    history: History<GameState>,
+    // the blocklist as it was last written to disk
+    saved_blocklist: Blocklist,
+    show_blocklist: bool,
+    past_answers_path: String,
+    priors_path: String,
+    tree_path: String,
+    // what was last found out about the decision tree, loading or verifying it
+    tree_status: String,
+    opener_text: String,
+    // the best few first guesses from the last opener analysis, or why it couldn't be saved
+    openers_status: String,
+    // every first guess being ranked on another thread, nothing if it was cancelled
+    pending_openers: Option<Task<Option<Openers>>>,
+    show_settings: bool,
+    // the last finished game graded against the solver, and the grading being worked out
+    game_analysis: Option<GameAnalysis>,
+    pending_analysis: Option<Task<GameAnalysis>>,
+    // where the report was saved, or why it couldn't be
+    game_analysis_status: String,
+    show_analysis: bool,
+    // a pasted share block to import, and what happened to the last import or copy
+    share_text: String,
+    share_status: String,
+    show_share: bool,
+    // the answers that fit the pasted share blocks, most likely first, and the ranking being
+    // worked out
+    inferred: Vec<(f64, String)>,
+    pending_inference: Option<Task<Vec<(f64, String)>>>,
+    // the session as it was last written to disk, and whether anything saved in it may have changed
+    // since
+    saved_session: Session,
+    session_dirty: bool,
+    // a session file to save to or open, and what happened to the last save or open
+    session_path: String,
+    session_status: String,
 }
 
 impl Default for WordleSolve {
     fn default() -> Self {
         Self {
-            words_url: "https://raw.githubusercontent.com/tabatkins/wordle-list/main/words".to_owned(),
+            words_url: DEFAULT_DICTIONARY_URL.to_owned(),
             board: Board::default(),
             guess: "".to_string(),
             guess_num: 0,
-            statistics: Statistics::default(),
+            statistics: Default::default(),
+            candidate_search: "".to_string(),
+            candidates_key: "".to_string(),
+            shown_candidates: Vec::new(),
+            candidate_count: 0,
+            conflicts: Vec::new(),
+            words_version: 0,
+            analysis_key: "".to_string(),
+            correction: None,
+            guarantee: None,
+            endgame: None,
+            pending_checks: None,
+            pending_guess: None,
+            pending_download: None,
+            download_status: "".to_string(),
+            pending_patterns: None,
+            patterns_status: "".to_string(),
+            multi: None,
+            multi_key: "".to_string(),
+            multi_counts: Vec::new(),
+            host: None,
+            host_guess: "".to_string(),
+            host_seed: "".to_string(),
+            host_status: "".to_string(),
+            host_commentary: Vec::new(),
+            pending_commentary: Vec::new(),
+            history: History::default(),
+            saved_blocklist: Blocklist::default(),
+            show_blocklist: false,
+            past_answers_path: PastAnswers::path().to_string_lossy().to_string(),
+            priors_path: Priors::path().to_string_lossy().to_string(),
+            tree_path: DecisionTree::path().to_string_lossy().to_string(),
+            tree_status: "".to_string(),
+            opener_text: "".to_string(),
+            openers_status: "".to_string(),
+            pending_openers: None,
+            show_settings: false,
+            game_analysis: None,
+            pending_analysis: None,
+            game_analysis_status: "".to_string(),
+            show_analysis: false,
+            share_text: "".to_string(),
+            share_status: "".to_string(),
+            show_share: false,
+            inferred: Vec::new(),
+            pending_inference: None,
+            saved_session: Session::default(),
+            session_dirty: false,
+            session_path: Session::saved_path().to_string_lossy().to_string(),
+            session_status: "".to_string(),
         }
     }
 }
 
 impl WordleSolve {
-    fn download(&mut self) -> Result<(), Box<dyn Error>> {
-        let response = reqwest::blocking::get(self.words_url.as_str())?;
+    // fetch the dictionary on another thread, the game starts again once it arrives unless the
+    // board is kept
+    fn download(&mut self, keep_board: bool) {
+        let url = self.words_url.clone();
+        self.download_status = "".to_string();
+        self.session_dirty = true;
+        self.pending_download = Some((keep_board, Task::spawn(move |_| load_source(&url).map_err(|e| e.to_string()))));
+    }
 
-        match response.text() {
-            Ok(content) => {
-                self.statistics.clear();
-                content.split("\n").for_each(|s| {
-                    self.statistics.add_word(&s.to_string().clone());
-                });
-                self.statistics.filters.clear();
-                self.board = Board::default();
-                self.guess = "".to_string();
-                self.guess_num = 0;
+    fn finish_download(&mut self) {
+        let Some(result) = self.pending_download.as_ref().and_then(|(_, task)| task.take()) else {
+            return;
+        };
+        let Some((keep_board, _)) = self.pending_download.take() else {
+            return;
+        };
+        let content = match result {
+            Ok(content) => content,
+            Err(e) => {
+                self.download_status = format!("Download failed: {}", e);
+                return;
+            }
+        };
+        self.cancel_guess();
+        self.words_version += 1;
+        self.session_dirty = true;
+        self.statistics.clear();
+        let skipped = self.statistics.add_words(&content);
+        if skipped > 0 {
+            self.download_status = format!("{} lines skipped, only words of a to z are used", skipped);
+        }
+        self.start_patterns();
+        if keep_board {
+            self.update_filters();
+            return;
+        }
+        if let Some(game) = &mut self.multi {
+            *game = MultiBoard::new(game.boards.len());
+        }
+        self.reset_host();
+        self.host = None;
+        self.statistics.filters.clear();
+        self.board = Board::default();
+        self.guess = "".to_string();
+        self.guess_num = 0;
+        self.history.clear();
+    }
+
+    // a whole dictionary's matrix can take a while to build, so guesses go without it until it's ready
+    fn start_patterns(&mut self) {
+        let (guesses, answers) = self.statistics.pattern_words();
+        self.pending_patterns = Some(Task::spawn(move |_| PatternMatrix::open(&guesses, &answers)));
+    }
+
+    fn finish_patterns(&mut self) {
+        let Some(result) = self.pending_patterns.as_ref().and_then(|task| task.take()) else {
+            return;
+        };
+        self.pending_patterns = None;
+        let (patterns, saved) = result;
+        self.statistics.set_patterns(patterns);
+        self.patterns_status = match saved {
+            Ok(()) => "".to_string(),
+            Err(e) => format!("The feedback for every guess couldn't be saved for next time: {}", e),
+        };
+    }
+
+    fn load_blocklist(&mut self) {
+        match Blocklist::load(&Blocklist::path()) {
+            Ok(blocklist) => {
+                self.saved_blocklist = blocklist.clone();
+                self.statistics.set_blocklist(blocklist);
+            }
+            Err(e) => {
+                println!("Error: {}", e);
+            }
+        }
+    }
+
+    fn load_past_answers(&mut self) {
+        match PastAnswers::load(&self.past_answers_path.clone().into()) {
+            Ok(past_answers) => {
+                self.words_version += 1;
+                self.statistics.set_past_answers(past_answers);
+            }
+            Err(e) => {
+                println!("Error: {}", e);
+            }
+        }
+    }
+
+    fn load_opener(&mut self) {
+        match Opener::load(&Opener::path()) {
+            Ok(opener) => {
+                self.opener_text = opener.to_string();
+                // the dictionary isn't there yet, so every word would be reported as missing
+                self.statistics.set_opener(opener);
             }
             Err(e) => {
                 println!("Error: {}", e);
             }
         }
-        Ok(())
+    }
+
+    fn pin_opener(&mut self) {
+        match Opener::parse(&self.opener_text) {
+            Ok(opener) => {
+                let saved = opener.save(&Opener::path());
+                let unknown = self.statistics.set_opener(opener);
+                self.openers_status = match saved {
+                    Err(e) => e.to_string(),
+                    Ok(()) if !unknown.is_empty() => format!("{} isn't in the dictionary, so it's never played", unknown.join(" and ")),
+                    Ok(()) => "".to_string(),
+                };
+            }
+            Err(e) => {
+                self.openers_status = e;
+            }
+        }
+    }
+
+    // use the saved opener analysis for the dictionary and strategy, if there is one
+    fn load_openers(&mut self) {
+        let key = self.statistics.openers_key();
+        if let Ok(openers) = Openers::load(&Openers::path(&key)) {
+            self.statistics.set_openers(openers);
+        }
+    }
+
+    // rank every first guess on another thread, scoring the whole dictionary is the slowest thing
+    // the app does
+    fn start_openers(&mut self) {
+        if let Some(task) = self.pending_openers.take() {
+            task.progress().cancel();
+        }
+        let statistics = self.statistics.clone();
+        self.pending_openers = Some(Task::spawn(move |progress| statistics.analyse_openers_with(progress)));
+        self.openers_status = "".to_string();
+    }
+
+    fn finish_openers(&mut self) {
+        let Some(result) = self.pending_openers.as_ref().and_then(|task| task.take()) else {
+            return;
+        };
+        self.pending_openers = None;
+        let Some(openers) = result else {
+            self.openers_status = "Cancelled".to_string();
+            return;
+        };
+        let best: Vec<&str> = openers.ranked().iter().take(5).map(|(_, word)| word.as_str()).collect();
+        self.openers_status = match openers.save() {
+            Ok(()) => format!("Best: {}", best.join(", ")),
+            Err(e) => e.to_string(),
+        };
+        self.statistics.set_openers(openers);
+    }
+
+    fn load_tree(&mut self) {
+        match DecisionTree::load(&self.tree_path.clone().into()) {
+            Ok(tree) => {
+                self.tree_status = format!("{} answers", tree.len());
+                self.statistics.set_tree(tree);
+            }
+            Err(e) => {
+                self.tree_status = e.to_string();
+            }
+        }
+    }
+
+    // check the tree against the dictionary as it is before any guesses
+    fn verify_tree(&mut self) {
+        let state = GameState { blocklist: self.statistics.blocklist().clone(), ..Default::default() };
+        let answers = self.statistics.candidates_for(&state);
+        self.tree_status = self.statistics.tree().verify(&answers, &self.statistics.guesses()).to_string();
+    }
+
+    // load the lists the settings point at, leaving out the ones that don't exist
+    fn load_lists(&mut self) {
+        if std::path::Path::new(&self.past_answers_path).exists() {
+            self.load_past_answers();
+        }
+        if std::path::Path::new(&self.priors_path).exists() {
+            self.load_priors();
+        }
+        if std::path::Path::new(&self.tree_path).exists() {
+            self.load_tree();
+        }
+    }
+
+    fn load_priors(&mut self) {
+        match Priors::load(&self.priors_path.clone().into()) {
+            Ok(priors) => {
+                self.statistics.set_priors(priors);
+            }
+            Err(e) => {
+                println!("Error: {}", e);
+            }
+        }
+    }
+
+    // write the blocklist out whenever a rejection, restore, undo or redo changes it
+    fn save_blocklist(&mut self) {
+        if *self.statistics.blocklist() == self.saved_blocklist {
+            return;
+        }
+        self.saved_blocklist = self.statistics.blocklist().clone();
+        if let Err(e) = self.saved_blocklist.save(&Blocklist::path()) {
+            println!("Error: {}", e);
+        }
+    }
+
+    // the parts of the app that are saved between launches
+    fn session(&self) -> Session {
+        Session {
+            words_url: self.words_url.clone(),
+            downloaded: self.statistics.len() > 0 || self.pending_download.is_some(),
+            board: self.board.clone(),
+            strategy: self.statistics.strategy(),
+            lookahead: self.statistics.lookahead(),
+            endgame_threshold: self.statistics.endgame_threshold(),
+            endgame_goal: self.statistics.endgame_goal(),
+            past_answer_mode: self.statistics.past_answer_mode(),
+            past_answers_path: self.past_answers_path.clone(),
+            priors_path: self.priors_path.clone(),
+            tree_path: self.tree_path.clone(),
+            blocklist: self.statistics.blocklist().clone(),
+        }
+    }
+
+    // pick a session back up, downloading its dictionary again if it had one, the words it rejected
+    // are added to the ones already rejected rather than replacing them
+    fn restore_session(&mut self, session: Session) {
+        self.cancel_guess();
+        self.set_board_count(1);
+        self.reset_host();
+        self.host = None;
+        self.words_url = session.words_url;
+        if session.downloaded {
+            self.download(true);
+        }
+        self.statistics.set_strategy(session.strategy);
+        self.statistics.set_lookahead(session.lookahead);
+        self.statistics.set_endgame_threshold(session.endgame_threshold);
+        self.statistics.set_endgame_goal(session.endgame_goal);
+        self.statistics.set_past_answer_mode(session.past_answer_mode);
+        self.past_answers_path = session.past_answers_path;
+        self.priors_path = session.priors_path;
+        self.tree_path = session.tree_path;
+        let mut blocklist = self.statistics.blocklist().clone();
+        for (word, reason) in session.blocklist.iter() {
+            blocklist.insert(word, *reason);
+        }
+        self.statistics.set_blocklist(blocklist);
+        self.set_played_board(session.board);
+        self.update_filters();
+        self.history.clear();
+        self.saved_session = self.session();
+    }
+
+    fn load_session(&mut self, path: &std::path::PathBuf) {
+        match Session::load(path) {
+            Ok(session) => self.restore_session(session),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    // write the session out whenever the board, the dictionary or a setting changes
+    fn save_session(&mut self) {
+        if !self.session_dirty {
+            return;
+        }
+        self.session_dirty = false;
+        let session = self.session();
+        if session == self.saved_session {
+            return;
+        }
+        if let Err(e) = session.save(&Session::path()) {
+            println!("Error: {}", e);
+        }
+        self.saved_session = session;
+    }
+
+    fn save_session_as(&mut self) {
+        self.session_status = match self.session().save(&self.session_path.clone().into()) {
+            Ok(()) => "Saved".to_string(),
+            Err(e) => e.to_string(),
+        };
+    }
+
+    fn open_session(&mut self) {
+        match Session::load(&self.session_path.clone().into()) {
+            Ok(session) => {
+                self.restore_session(session);
+                self.load_lists();
+                self.session_status = format!("Opened with {} rows played", self.guess_num);
+            }
+            Err(e) => self.session_status = e.to_string(),
+        }
+    }
+
+    fn update_filters(&mut self) {
+        self.statistics.set_board(&self.board);
+    }
+
+    fn state(&mut self) -> GameState {
+        self.update_filters();
+        GameState {
+            board: self.board.clone(),
+            guess: self.guess.clone(),
+            guess_num: self.guess_num,
+            blocklist: self.statistics.blocklist().clone(),
+            candidates: self.statistics.candidate_count(),
+        }
+    }
+
+    fn restore(&mut self, state: GameState) {
+        self.cancel_guess();
+        self.board = state.board;
+        self.guess = state.guess;
+        self.guess_num = state.guess_num;
+        self.statistics.set_blocklist(state.blocklist);
+        self.update_filters();
+        self.session_dirty = true;
+    }
+
+    // remember the current state before changing it so it can be undone, every change to the board
+    // or the blocklist comes through here
+    fn checkpoint(&mut self) {
+        self.session_dirty = true;
+        let state = self.state();
+        self.history.push(state);
+    }
+
+    fn undo(&mut self) {
+        let current = self.state();
+        if let Some(state) = self.history.undo(current) {
+            self.restore(state);
+        }
+    }
+
+    fn redo(&mut self) {
+        let current = self.state();
+        if let Some(state) = self.history.redo(current) {
+            self.restore(state);
+        }
+    }
+
+    // ctrl+z and ctrl+shift+z, unless a text box has focus and wants them for itself
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.memory(|memory| memory.focused().is_some()) {
+            return;
+        }
+        let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        if ctx.input_mut(|input| input.consume_shortcut(&redo)) {
+            self.redo();
+        } else if ctx.input_mut(|input| input.consume_shortcut(&undo)) {
+            self.undo();
+        }
+    }
+
+    // drop the current guess from the dictionary and guess again from the rows before it
+    fn reject_guess(&mut self, reason: RejectReason) {
+        self.checkpoint();
+        self.statistics.remove_word(&self.guess, reason);
+        let mut board = self.board.clone();
+        board.words[self.guess_num - 1] = Default::default();
+        self.statistics.set_board(&board);
+        self.start_guess(self.guess_num - 1);
+    }
+
+    // work the guess out on another thread so the window keeps responding, a row past the last
+    // guess starts a new one
+    fn start_guess(&mut self, row: usize) {
+        let mut statistics = self.statistics.clone();
+        self.pending_guess = Some((row, Task::spawn(move |progress| statistics.guess_with(progress))));
+    }
+
+    // play the guess once it has been worked out
+    fn finish_guess(&mut self) {
+        let Some(guess) = self.pending_guess.as_ref().and_then(|(_, task)| task.take()) else {
+            return;
+        };
+        let Some((row, _)) = self.pending_guess.take() else {
+            return;
+        };
+        if let Some(game) = &mut self.multi {
+            if !guess.is_empty() {
+                game.play(&guess);
+            }
+            self.guess = guess;
+            return;
+        }
+        if row == self.guess_num {
+            self.checkpoint();
+            self.guess_num += 1;
+        }
+        self.guess = guess;
+        self.board.set_word(row, &self.guess);
+        self.session_dirty = true;
+    }
+
+    // the same guess for every unsolved board, worked out on another thread
+    fn start_multi_guess(&mut self) {
+        let Some(game) = self.multi.clone() else {
+            return;
+        };
+        let mut statistics = self.statistics.clone();
+        self.pending_guess = Some((game.guess_num, Task::spawn(move |progress| statistics.multi_guess_with(&game, progress))));
+    }
+
+    // switching between one board and several starts a new game
+    fn set_board_count(&mut self, count: usize) {
+        if count == self.multi.as_ref().map_or(1, |game| game.boards.len()) {
+            return;
+        }
+        self.cancel_guess();
+        self.multi = if count > 1 { Some(MultiBoard::new(count)) } else { None };
+        self.guess = "".to_string();
+    }
+
+    // stop working out a guess that is no longer wanted
+    fn cancel_guess(&mut self) {
+        if let Some((_, task)) = self.pending_guess.take() {
+            task.progress().cancel();
+        }
+    }
+
+    fn board_key(&self) -> String {
+        self.board.words.iter()
+            .flat_map(|word| word.letters.iter())
+            .map(|letter| format!("{}{:?}", letter.value, letter.get_state()))
+            .collect()
+    }
+
+    // only look for a correction once the feedback leaves nothing to guess, and only check for a
+    // guaranteed solve once a guess has been made with the worst case strategy, the endgame is
+    // only searched once a guess has been made too
+    fn update_analysis(&mut self, ctx: &egui::Context) {
+        if let Some((correction, guarantee, endgame)) = self.pending_checks.as_ref().and_then(|task| task.take()) {
+            self.correction = correction;
+            self.guarantee = guarantee;
+            self.endgame = endgame;
+            self.pending_checks = None;
+        }
+        let key = format!("{}{}{}{}", self.board_key(), self.statistics.strategy().name(),
+                          self.statistics.endgame_threshold(), self.statistics.endgame_goal().name());
+        if key != self.analysis_key {
+            self.analysis_key = key;
+            if let Some(task) = self.pending_checks.take() {
+                task.progress().cancel();
+            }
+            self.correction = None;
+            self.guarantee = None;
+            self.endgame = None;
+            let statistics = self.statistics.clone();
+            let guessed = self.guess_num > 0;
+            self.pending_checks = Some(Task::spawn(move |progress| {
+                let candidates = statistics.candidate_count();
+                let correction = if statistics.len() > 0 && candidates == 0 {
+                    statistics.suggest_correction()
+                } else {
+                    None
+                };
+                let guarantee = if statistics.strategy() == StrategyKind::Minimax && guessed && candidates > 0 {
+                    Some(statistics.guarantee())
+                } else {
+                    None
+                };
+                let endgame = if guessed && candidates > 0 {
+                    statistics.endgame_with(progress)
+                } else {
+                    None
+                };
+                (correction, guarantee, endgame)
+            }));
+        }
+        if self.pending_checks.is_some() {
+            ctx.request_repaint();
+        }
+    }
+
+    // play the given word in the current row, starting a new row if nothing has been guessed yet
+    fn play_word(&mut self, word: &str) {
+        self.checkpoint();
+        if self.guess_num == 0 {
+            self.guess_num = 1;
+        }
+        self.guess = word.to_string();
+        self.board.set_word(self.guess_num - 1, &self.guess);
+    }
+
+    // grade every row of the board against the solver on another thread
+    fn start_analysis(&mut self, board: Board) {
+        if let Some(task) = self.pending_analysis.take() {
+            task.progress().cancel();
+        }
+        let mut statistics = self.statistics.clone();
+        self.pending_analysis = Some(Task::spawn(move |progress| statistics.analyse_with(&board, progress)));
+        self.game_analysis = None;
+        self.game_analysis_status = "".to_string();
+        self.show_analysis = true;
+    }
+
+    fn analysis_window(&mut self, ctx: &egui::Context) {
+        if let Some(analysis) = self.pending_analysis.as_ref().and_then(|task| task.take()) {
+            self.game_analysis = Some(analysis);
+            self.pending_analysis = None;
+        }
+        if self.pending_analysis.is_some() {
+            ctx.request_repaint();
+        }
+        let mut open = self.show_analysis;
+        let mut save_clicked = false;
+        egui::Window::new("Analysis").open(&mut open).show(ctx, |ui| {
+            if let Some(task) = &self.pending_analysis {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Replaying the game {:.0}%", task.progress().fraction() * 100.0));
+                });
+            }
+            let Some(analysis) = &self.game_analysis else {
+                return;
+            };
+            egui::Grid::new("analysis").striped(true).show(ui, |ui| {
+                for heading in ["Guess", "Candidates", "Bits", "Expected", "Guesses", "Solver", "Expected", "Guesses", "Skill", "Luck"] {
+                    ui.strong(heading);
+                }
+                ui.end_row();
+                for row in analysis.rows.iter() {
+                    let commentary = &row.commentary;
+                    ui.label(commentary.guess.as_str());
+                    ui.label(format!("{} → {}", commentary.before, commentary.after));
+                    ui.label(format!("{:.2}", commentary.bits()));
+                    ui.label(format!("{:.2}", commentary.expected_bits));
+                    ui.label(format!("{:.2}", row.expected_guesses));
+                    ui.label(commentary.solver_guess.as_str());
+                    ui.label(format!("{:.2}", commentary.solver_expected_bits));
+                    ui.label(format!("{:.2}", row.solver_expected_guesses));
+                    ui.label(format!("{:.0}", row.skill()));
+                    ui.label(format!("{:+.2}", row.luck()));
+                    ui.end_row();
+                }
+            });
+            ui.label(format!("Skill {:.0} out of 100, luck {:+.2} bits", analysis.skill(), analysis.luck()));
+            ui.horizontal(|ui| {
+                if ui.button("Copy Markdown").on_hover_text("Copy the report as a Markdown table").clicked() {
+                    let markdown = analysis.markdown();
+                    ui.output_mut(|output| output.copied_text = markdown);
+                }
+                if ui.button("Save").on_hover_text("Save the report as Markdown").clicked() {
+                    save_clicked = true;
+                }
+                ui.label(self.game_analysis_status.as_str());
+            });
+        });
+        self.show_analysis = open;
+
+        if let (true, Some(analysis)) = (save_clicked, &self.game_analysis) {
+            let path = GameAnalysis::path();
+            self.game_analysis_status = match analysis.save(&path) {
+                Ok(()) => format!("Saved to {}", path.display()),
+                Err(e) => format!("Error: {}", e),
+            };
+        }
+    }
+
+    // the board from a pasted share block that has the guesses beside the squares
+    fn import_share(&mut self) {
+        match ShareGrid::parse(&self.share_text).and_then(|grid| grid.board()) {
+            Ok(board) => self.import_board(board),
+            Err(e) => self.share_status = e,
+        }
+    }
+
+    // the pasted text read as rows like crane:..y.g slate:gg..y
+    fn import_notation(&mut self) {
+        match Notation::parse(&self.share_text) {
+            Ok(board) => self.import_board(board),
+            Err(e) => self.share_status = e.to_string(),
+        }
+    }
+
+    fn import_board(&mut self, board: Board) {
+        self.cancel_guess();
+        self.checkpoint();
+        self.set_played_board(board);
+        self.share_status = format!("Imported {} rows", self.guess_num);
+    }
+
+    // carry on from a board played elsewhere, its last row is the guess whose colours are being set
+    fn set_played_board(&mut self, board: Board) {
+        self.guess_num = board.words.iter()
+            .take_while(|word| word.letters.iter().all(|letter| letter.get_state() != LetterState::Disabled))
+            .count();
+        self.guess = if self.guess_num > 0 { board.words[self.guess_num - 1].value() } else { "".to_string() };
+        self.board = board;
+    }
+
+    // rank the answers that fit every pasted share block on another thread
+    fn start_inference(&mut self) {
+        if let Some(task) = self.pending_inference.take() {
+            task.progress().cancel();
+        }
+        self.inferred.clear();
+        match ShareGrid::parse_all(&self.share_text) {
+            Ok(grids) => {
+                let statistics = self.statistics.clone();
+                self.pending_inference = Some(Task::spawn(move |progress| statistics.infer_with(&grids, progress)));
+                self.share_status = "".to_string();
+            }
+            Err(e) => self.share_status = e,
+        }
+    }
+
+    fn share_window(&mut self, ctx: &egui::Context) {
+        if let Some(ranked) = self.pending_inference.as_ref().and_then(|task| task.take()) {
+            self.share_status = format!("{} answers fit", ranked.len());
+            self.inferred = ranked;
+            self.pending_inference = None;
+        }
+        if self.pending_inference.is_some() {
+            ctx.request_repaint();
+        }
+        let mut open = self.show_share;
+        let mut import_clicked = false;
+        let mut infer_clicked = false;
+        let mut notation_clicked = false;
+        egui::Window::new("Share").open(&mut open).show(ctx, |ui| {
+            ui.add(egui::TextEdit::multiline(&mut self.share_text).desired_rows(8).desired_width(320.0)
+                .hint_text("Wordle 1,234 4/6\n\ncrane ⬛⬛🟨⬛🟩\n..."));
+            ui.horizontal(|ui| {
+                if ui.button("Import").on_hover_text("Play the pasted rows, each needs its guess beside the squares").clicked() {
+                    import_clicked = true;
+                }
+                if ui.button("Infer").on_hover_text("Find the answers that fit every pasted share block, no guesses needed").clicked() {
+                    infer_clicked = true;
+                }
+                if ui.button("Import Board").on_hover_text("Play rows written like crane:..y.g slate:gg..y").clicked() {
+                    notation_clicked = true;
+                }
+                for (label, high_contrast) in [("Copy", false), ("Copy High Contrast", true)] {
+                    if ui.button(label).on_hover_text("Copy the board as a share block").clicked() {
+                        let text = ShareGrid::from_board(&self.board, Some(ShareGrid::today_number())).render(high_contrast);
+                        ui.output_mut(|output| output.copied_text = text);
+                        self.share_status = "Copied".to_string();
+                    }
+                }
+                if ui.button("Copy Board").on_hover_text("Copy the board as rows like crane:..y.g slate:gg..y, for bug reports or the command line").clicked() {
+                    ui.output_mut(|output| output.copied_text = Notation::print(&self.board));
+                    self.share_status = "Copied".to_string();
+                }
+            });
+            ui.label(self.share_status.as_str());
+            if let Some(task) = &self.pending_inference {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Comparing every guess {:.0}%", task.progress().fraction() * 100.0));
+                });
+            }
+            for (likelihood, word) in self.inferred.iter().take(10) {
+                ui.label(format!("{} {:.1}%", word, likelihood * 100.0));
+            }
+        });
+        self.show_share = open;
+        if import_clicked {
+            self.import_share();
+        }
+        if infer_clicked {
+            self.start_inference();
+        }
+        if notation_clicked {
+            self.import_notation();
+        }
+    }
+
+    fn blocklist_window(&mut self, ctx: &egui::Context) {
+        let mut restored = None;
+        let mut open = self.show_blocklist;
+        egui::Window::new("Rejected Words").open(&mut open).show(ctx, |ui| {
+            if self.statistics.blocklist().is_empty() {
+                ui.label("No rejected words");
+            } else {
+                ui.label(format!("{} rejected words", self.statistics.blocklist().len()));
+            }
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                egui::Grid::new("blocklist").show(ui, |ui| {
+                    for (word, reason) in self.statistics.blocklist().iter() {
+                        ui.label(word.as_str());
+                        ui.label(reason.to_string());
+                        if ui.button("Restore").on_hover_text("Allow this word again").clicked() {
+                            restored = Some(word.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        self.show_blocklist = open;
+
+        if let Some(word) = restored {
+            self.checkpoint();
+            self.statistics.restore_word(&word);
+        }
+    }
+
+    fn settings_window(&mut self, ctx: &egui::Context) {
+        self.finish_openers();
+        if self.pending_openers.is_some() {
+            ctx.request_repaint();
+        }
+        let mut open = self.show_settings;
+        egui::Window::new("Settings").open(&mut open).show(ctx, |ui| {
+            egui::Grid::new("settings").show(ui, |ui| {
+                ui.label("Boards:");
+                let mut count = self.multi.as_ref().map_or(1, |game| game.boards.len());
+                egui::ComboBox::from_id_source("boards")
+                    .selected_text(format!("{} ({})", count, MultiBoard::name(count)))
+                    .show_ui(ui, |ui| {
+                        for option in BOARD_COUNTS {
+                            ui.selectable_value(&mut count, option, format!("{} ({})", option, MultiBoard::name(option)));
+                        }
+                    });
+                self.set_board_count(count);
+                ui.end_row();
+
+                ui.label("Strategy:");
+                let mut strategy = self.statistics.strategy();
+                egui::ComboBox::from_id_source("strategy")
+                    .selected_text(strategy.to_string())
+                    .show_ui(ui, |ui| {
+                        for option in StrategyKind::ALL {
+                            ui.selectable_value(&mut strategy, option, option.to_string());
+                        }
+                    });
+                if strategy != self.statistics.strategy() {
+                    self.statistics.set_strategy(strategy);
+                    self.session_dirty = true;
+                }
+                ui.end_row();
+
+                ui.label("Lookahead:");
+                ui.horizontal(|ui| {
+                    let mut lookahead = self.statistics.lookahead();
+                    ui.add(egui::DragValue::new(&mut lookahead.breadth).clamp_range(1..=100).suffix(" guesses"))
+                        .on_hover_text("How many of the best guesses the lookahead strategy looks two steps ahead for");
+                    let mut seconds = lookahead.time_limit.as_secs_f64();
+                    ui.add(egui::DragValue::new(&mut seconds).clamp_range(0.1..=60.0).speed(0.1).suffix(" s"))
+                        .on_hover_text("How long the lookahead strategy may take before using the best guess so far");
+                    lookahead.time_limit = std::time::Duration::from_secs_f64(seconds);
+                    if lookahead != self.statistics.lookahead() {
+                        self.statistics.set_lookahead(lookahead);
+                        self.session_dirty = true;
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Endgame:");
+                ui.horizontal(|ui| {
+                    let mut threshold = self.statistics.endgame_threshold();
+                    ui.add(egui::DragValue::new(&mut threshold).clamp_range(0..=200).suffix(" candidates"))
+                        .on_hover_text("Search every guess exactly once this few candidates are left, 0 turns it off");
+                    if threshold != self.statistics.endgame_threshold() {
+                        self.statistics.set_endgame_threshold(threshold);
+                        self.session_dirty = true;
+                    }
+
+                    let mut goal = self.statistics.endgame_goal();
+                    egui::ComboBox::from_id_source("endgame_goal")
+                        .selected_text(goal.to_string())
+                        .show_ui(ui, |ui| {
+                            for option in EndgameGoal::ALL {
+                                ui.selectable_value(&mut goal, option, option.to_string());
+                            }
+                        });
+                    if goal != self.statistics.endgame_goal() {
+                        self.statistics.set_endgame_goal(goal);
+                        self.session_dirty = true;
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Past Answers:");
+                let mut mode = self.statistics.past_answer_mode();
+                egui::ComboBox::from_id_source("past_answer_mode")
+                    .selected_text(mode.to_string())
+                    .show_ui(ui, |ui| {
+                        for option in PastAnswerMode::ALL {
+                            ui.selectable_value(&mut mode, option, option.to_string());
+                        }
+                    });
+                // changing the mode starts the candidates again, so only when it really changes
+                if mode != self.statistics.past_answer_mode() {
+                    self.statistics.set_past_answer_mode(mode);
+                    self.session_dirty = true;
+                }
+                ui.end_row();
+
+                ui.label(format!("{} loaded", self.statistics.past_answers().len()));
+                ui.horizontal(|ui| {
+                    self.session_dirty |= ui.add(egui::TextEdit::singleline(&mut self.past_answers_path).desired_width(400.0)).changed();
+                    if ui.button("Load").on_hover_text("Load a list of past answers, one per line, optionally dated").clicked() {
+                        self.load_past_answers();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Word Frequencies:");
+                ui.label(format!("{} loaded", self.statistics.priors().len()));
+                ui.end_row();
+
+                ui.label("");
+                ui.horizontal(|ui| {
+                    self.session_dirty |= ui.add(egui::TextEdit::singleline(&mut self.priors_path).desired_width(400.0)).changed();
+                    if ui.button("Load").on_hover_text("Load a word frequency list, a word and a count or probability per line").clicked() {
+                        self.load_priors();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Opener:");
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.opener_text).desired_width(120.0).hint_text("crane, slate"));
+                    if ui.button("Pin").on_hover_text("Always open with this word, or pair of words, blank to let the strategy choose").clicked() {
+                        self.pin_opener();
+                    }
+                    if let Some(task) = &self.pending_openers {
+                        ui.spinner();
+                        ui.label(format!("Ranking {:.0}%", task.progress().fraction() * 100.0));
+                        if ui.button("Cancel").on_hover_text("Stop ranking, nothing is saved").clicked() {
+                            task.progress().cancel();
+                        }
+                    } else if ui.button("Analyse").on_hover_text("Rank every first guess with the strategy and save it, this can take a while").clicked() {
+                        self.start_openers();
+                    }
+                    ui.label(self.openers_status.as_str());
+                });
+                ui.end_row();
+
+                ui.label("Decision Tree:");
+                ui.label(if self.statistics.tree().is_empty() { "None loaded" } else { "Guessing from the tree" });
+                ui.end_row();
+
+                ui.label("");
+                ui.horizontal(|ui| {
+                    self.session_dirty |= ui.add(egui::TextEdit::singleline(&mut self.tree_path).desired_width(400.0)).changed();
+                    if ui.button("Load").on_hover_text("Load a tree made with build-tree, one answer per line as crane:..y.g pairs").clicked() {
+                        self.load_tree();
+                    }
+                    if ui.button("Verify").on_hover_text("Check the tree solves every word in the dictionary").clicked() {
+                        self.verify_tree();
+                    }
+                    if ui.button("Clear").on_hover_text("Stop guessing from the tree").clicked() {
+                        self.statistics.set_tree(DecisionTree::default());
+                        self.tree_status = "".to_string();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("");
+                ui.label(self.tree_status.as_str());
+                ui.end_row();
+
+                ui.label("Session:");
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.session_path).desired_width(400.0));
+                    if ui.button("Save").on_hover_text("Save the board, dictionary, settings and rejected words to this file").clicked() {
+                        self.save_session_as();
+                    }
+                    if ui.button("Open").on_hover_text("Pick up a saved session, downloading its dictionary again").clicked() {
+                        self.open_session();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("");
+                ui.label(self.session_status.as_str());
+                ui.end_row();
+            });
+        });
+        self.show_settings = open;
+    }
+
+    // how many candidates each board has left, only worked out again when a board changes
+    fn update_multi_counts(&mut self) {
+        let Some(game) = &self.multi else {
+            return;
+        };
+        let key: String = game.boards.iter()
+            .flat_map(|board| board.words.iter())
+            .flat_map(|word| word.letters.iter())
+            .map(|letter| format!("{}{:?}", letter.value, letter.get_state()))
+            .chain(std::iter::once(self.words_key()))
+            .collect();
+        if key == self.multi_key {
+            return;
+        }
+        self.multi_counts = game.boards.iter().map(|board| self.statistics.board_candidate_count(board)).collect();
+        self.multi_key = key;
+    }
+
+    // every board side by side, four to a row, the same guess goes on each one that isn't solved
+    fn multi_board_panel(&mut self, ctx: &egui::Context, busy: bool) {
+        self.update_multi_counts();
+        let mut toggled = None;
+        let mut guess_clicked = false;
+        let mut again_clicked = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Button,
+                egui::FontId::new(16.0, eframe::epaint::FontFamily::Monospace),
+            );
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Body,
+                egui::FontId::new(14.0, eframe::epaint::FontFamily::Monospace),
+            );
+            let Some(game) = &self.multi else {
+                return;
+            };
+            for (chunk_idx, chunk) in game.boards.chunks(4).enumerate() {
+                ui.horizontal(|ui| {
+                    for (offset, board) in chunk.iter().enumerate() {
+                        let board_idx = chunk_idx * 4 + offset;
+                        ui.vertical(|ui| {
+                            let status = if MultiBoard::is_solved(board) {
+                                "Solved".to_string()
+                            } else {
+                                format!("{} candidates", self.multi_counts.get(board_idx).copied().unwrap_or(0))
+                            };
+                            ui.label(status);
+                            egui::Grid::new(format!("board_{}", board_idx)).spacing([2.0, 2.0]).show(ui, |ui| {
+                                for (row, word) in board.words.iter().enumerate() {
+                                    for (col, letter) in word.letters.iter().enumerate() {
+                                        let text = egui::RichText::new(letter.value.to_string()).color(letter.get_text_color());
+                                        let button = egui::Button::new(text).fill(letter.get_fill_color());
+                                        if ui.add_enabled(!busy, button).clicked() && letter.get_state() != LetterState::Disabled {
+                                            toggled = Some((board_idx, Tile { row, col }));
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                        ui.add_space(12.0);
+                    }
+                });
+            }
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!busy && !game.is_over(), egui::Button::new("Guess")).on_hover_text("Guess the next word for every board").clicked() {
+                    guess_clicked = true;
+                }
+                if ui.button("Play Again").on_hover_text("Start again with empty boards").clicked() {
+                    again_clicked = true;
+                }
+                if ui.button("Settings").on_hover_text("Strategy, boards, past answers and word frequencies").clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+            });
+            if let Some((_, task)) = &self.pending_guess {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Thinking {:.0}%", task.progress().fraction() * 100.0));
+                    if ui.button("Cancel").on_hover_text("Play the best guess found so far").clicked() {
+                        task.progress().cancel();
+                    }
+                });
+            }
+            ui.label(game.to_string());
+            if self.guess.is_empty() && game.guess_num > 0 && !game.is_over() {
+                ui.label(egui::RichText::new("No valid guesses left").color(egui::Color32::RED));
+            }
+        });
+        if let (Some(game), Some((board_idx, tile))) = (&mut self.multi, toggled) {
+            game.boards[board_idx].words[tile.row].letters[tile.col].toggle();
+        }
+        if guess_clicked {
+            self.start_multi_guess();
+        }
+        if again_clicked {
+            let count = self.multi.as_ref().map_or(1, |game| game.boards.len());
+            self.cancel_guess();
+            self.multi = Some(MultiBoard::new(count));
+            self.guess = "".to_string();
+        }
+    }
+
+    // host a new game against every word that could be the answer
+    fn start_host(&mut self) {
+        self.reset_host();
+        self.host = Some(HostGame::evil(self.statistics.position()));
+    }
+
+    // host a game with a secret word picked by the seed, or by today's date without one
+    fn start_practice(&mut self) {
+        self.reset_host();
+        let seed = match self.host_seed.trim() {
+            "" => HostGame::today(),
+            seed => seed.to_string(),
+        };
+        match HostGame::practice(self.statistics.position(), &seed) {
+            Some(game) => self.host = Some(game),
+            None => self.host_status = "No words to pick from, Download Words First".to_string(),
+        }
+    }
+
+    fn reset_host(&mut self) {
+        self.cancel_guess();
+        for task in self.pending_commentary.drain(..) {
+            task.progress().cancel();
+        }
+        self.statistics.set_board(&Board::default());
+        self.host_guess = "".to_string();
+        self.host_status = "".to_string();
+        self.host_commentary.clear();
+    }
+
+    // the commentary for each row as it is worked out, keeping them in the order they were played
+    fn finish_commentary(&mut self) {
+        while let Some(commentary) = self.pending_commentary.first().and_then(|task| task.take()) {
+            self.host_commentary.push(commentary);
+            self.pending_commentary.remove(0);
+        }
+    }
+
+    fn play_host_guess(&mut self) {
+        let Some(game) = &mut self.host else {
+            return;
+        };
+        match game.play(&self.host_guess) {
+            Ok(_) => {
+                self.host_guess = "".to_string();
+                self.host_status = "".to_string();
+                let (board, row) = (game.board.clone(), game.guess_num - 1);
+                let mut statistics = self.statistics.clone();
+                self.pending_commentary.push(Task::spawn(move |progress| statistics.commentary_with(&board, row, progress)));
+            }
+            Err(e) => self.host_status = e,
+        }
+    }
+
+    // the user guesses and the app hands out the colours
+    fn host_panel(&mut self, ctx: &egui::Context) {
+        self.finish_commentary();
+        if !self.pending_commentary.is_empty() {
+            ctx.request_repaint();
+        }
+        let mut entered = false;
+        let mut evil_clicked = false;
+        let mut practice_clicked = false;
+        let mut analyse_clicked = false;
+        let mut leave_clicked = false;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Button,
+                egui::FontId::new(40.0, eframe::epaint::FontFamily::Monospace),
+            );
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Body,
+                egui::FontId::new(20.0, eframe::epaint::FontFamily::Monospace),
+            );
+            ui.horizontal(|ui| {
+                ui.style_mut().text_styles.insert(
+                    egui::TextStyle::Button,
+                    egui::FontId::new(20.0, eframe::epaint::FontFamily::Monospace),
+                );
+                if ui.button("Evil").on_hover_text("Start a game against a host that dodges your guesses for as long as it can").clicked() {
+                    evil_clicked = true;
+                }
+                if ui.button("Practice").on_hover_text("Start a game with a secret word picked by the seed").clicked() {
+                    practice_clicked = true;
+                }
+                ui.add(egui::TextEdit::singleline(&mut self.host_seed).desired_width(160.0).hint_text(HostGame::today()));
+                if ui.button("Solver").on_hover_text("Go back to solving").clicked() {
+                    leave_clicked = true;
+                }
+            });
+            let Some(game) = &self.host else {
+                ui.label(egui::RichText::new(self.host_status.as_str()).color(egui::Color32::RED));
+                return;
+            };
+            egui::Grid::new("host_squares").show(ui, |ui| {
+                for word in game.board.words.iter() {
+                    for letter in word.letters.iter() {
+                        let text = egui::RichText::new(letter.value.to_string()).color(letter.get_text_color());
+                        ui.add(egui::Button::new(text).fill(letter.get_fill_color()));
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.style_mut().text_styles.insert(
+                    egui::TextStyle::Button,
+                    egui::FontId::new(20.0, eframe::epaint::FontFamily::Monospace),
+                );
+                let response = ui.add_enabled(!game.is_over(), egui::TextEdit::singleline(&mut self.host_guess).desired_width(120.0).hint_text("guess"));
+                if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                    entered = true;
+                    response.request_focus();
+                }
+                if ui.add_enabled(!game.is_over(), egui::Button::new("Enter")).on_hover_text("Play this guess").clicked() {
+                    entered = true;
+                }
+                if ui.add_enabled(game.is_over(), egui::Button::new("Analyse")).on_hover_text("Grade every guess against the solver").clicked() {
+                    analyse_clicked = true;
+                }
+            });
+            ui.label(format!("{}: {}", if game.is_practice() { "Practice" } else { "Evil" }, game));
+            ui.label(egui::RichText::new(self.host_status.as_str()).color(egui::Color32::RED));
+
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Body,
+                egui::FontId::new(14.0, eframe::epaint::FontFamily::Monospace),
+            );
+            for commentary in self.host_commentary.iter() {
+                ui.label(commentary.to_string());
+            }
+            if let Some(task) = self.pending_commentary.first() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Working out the solver's guess {:.0}%", task.progress().fraction() * 100.0));
+                });
+            }
+        });
+        if entered {
+            self.play_host_guess();
+        }
+        if evil_clicked {
+            self.start_host();
+        }
+        if practice_clicked {
+            self.start_practice();
+        }
+        if let (true, Some(game)) = (analyse_clicked, &self.host) {
+            self.start_analysis(game.board.clone());
+        }
+        if leave_clicked {
+            self.reset_host();
+            self.host = None;
+        }
+    }
+
+    // what the candidates depend on besides the board
+    fn words_key(&self) -> String {
+        format!("{} {} {} {:?}", self.words_version, self.statistics.len(), self.statistics.blocklist(), self.statistics.past_answer_mode())
+    }
+
+    // the candidate list and the conflicts are only worked out again when something they depend on
+    // changes, not on every repaint
+    fn update_candidates(&mut self) {
+        let search = self.candidate_search.trim().to_lowercase();
+        let key = format!("{}{}#{}", self.board_key(), self.words_key(), search);
+        if key == self.candidates_key {
+            return;
+        }
+        self.candidates_key = key;
+        self.update_filters();
+        let candidates = self.statistics.candidates();
+        self.candidate_count = candidates.len();
+        self.shown_candidates = candidates.into_iter().filter(|word| word.contains(search.as_str())).collect();
+        self.conflicts = self.statistics.conflicts();
+    }
+
+    fn candidate_panel(&mut self, ctx: &egui::Context) {
+        self.update_candidates();
+        let mut played = None;
+
+        egui::SidePanel::right("candidates").exact_width(220.0).resizable(false).show(ctx, |ui| {
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Body,
+                egui::FontId::new(16.0, eframe::epaint::FontFamily::Monospace),
+            );
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Button,
+                egui::FontId::new(16.0, eframe::epaint::FontFamily::Monospace),
+            );
+
+            ui.add(egui::Label::new("Candidates: ".to_string() + &self.candidate_count.to_string()));
+            if ui.add(egui::TextEdit::singleline(&mut self.candidate_search).hint_text("Search")).changed() {
+                ctx.request_repaint();
+            }
+
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                let past_answers = self.statistics.past_answers();
+                for word in self.shown_candidates.iter() {
+                    let mut text = egui::RichText::new(word.as_str());
+                    let mut hover = "Play this word".to_string();
+                    if past_answers.contains(word) {
+                        text = text.color(egui::Color32::GRAY);
+                        hover = match past_answers.date(word) {
+                            Some(date) => format!("Play this word, the answer on {}", date),
+                            None => "Play this word, a past answer".to_string(),
+                        };
+                    }
+                    if ui.add(egui::Button::new(text).frame(false)).on_hover_text(hover).clicked() {
+                        played = Some(word.clone());
+                    }
+                }
+            });
+        });
+
+        if let Some(word) = played {
+            self.play_word(&word);
+        }
     }
 }
 
 impl eframe::App for WordleSolve {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let mut reset = false;
+        let mut reset = None;
+        let mut toggled = None;
+        self.save_session();
+        self.finish_guess();
+        self.finish_download();
+        self.finish_patterns();
+        if self.pending_guess.is_some() || self.pending_download.is_some() || self.pending_patterns.is_some() {
+            ctx.request_repaint();
+        }
+        let busy = self.pending_guess.is_some();
+        self.blocklist_window(ctx);
+        self.settings_window(ctx);
+        self.analysis_window(ctx);
+        self.share_window(ctx);
+        if self.host.is_some() || !self.host_status.is_empty() {
+            self.host_panel(ctx);
+            return;
+        }
+        if self.multi.is_some() {
+            self.multi_board_panel(ctx, busy);
+            self.save_blocklist();
+            return;
+        }
+        self.handle_shortcuts(ctx);
+        self.candidate_panel(ctx);
+        self.update_analysis(ctx);
+        let conflicts = self.conflicts.clone();
+        let mut apply_correction = false;
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.style_mut().text_styles.insert(
                 egui::TextStyle::Button,
@@ -95,15 +1451,20 @@ impl eframe::App for WordleSolve {
 
             egui::Grid::new("wordle_squares").show(ui, |ui| {
                 for (idx, row) in self.board.words.iter_mut().enumerate() {
-                    for col in row.letters.iter_mut() {
+                    for (col_idx, col) in row.letters.iter_mut().enumerate() {
                         let button_text = egui::RichText::new(col.value.to_string())
                             .color(col.get_text_color());
 
-                        let button = egui::Button::new(button_text).
+                        let mut button = egui::Button::new(button_text).
                             fill(col.get_fill_color());
 
-                        if ui.add(button).clicked() {
-                            col.toggle();
+                        let tile = Tile { row: idx, col: col_idx };
+                        if conflicts.iter().any(|conflict| conflict.tiles.contains(&tile)) {
+                            button = button.stroke(egui::Stroke::new(3.0, egui::Color32::RED));
+                        }
+
+                        if ui.add_enabled(!busy, button).clicked() && col.get_state() != LetterState::Disabled {
+                            toggled = Some(tile);
                         }
                     }
                     if self.guess_num == idx + 1 {
@@ -111,15 +1472,24 @@ impl eframe::App for WordleSolve {
 
                         let button = egui::Button::new(button_text);
 
-                        if ui.add(button).on_hover_text("Reject this guess").clicked() {
-                         reset = true;
+                        if ui.add_enabled(!busy, button).on_hover_text("The game doesn't accept this word").clicked() {
+                         reset = Some(RejectReason::NotAccepted);
+                        }
+
+                        let button = egui::Button::new(egui::RichText::new("👎"));
+                        if ui.add_enabled(!busy, button).on_hover_text("Don't suggest this word").clicked() {
+                            reset = Some(RejectReason::Unwanted);
                         }
                     }
                     ui.end_row();
 
                 }
             });
-            ui.horizontal(|ui| {
+            if let Some(tile) = toggled {
+                self.checkpoint();
+                self.board.words[tile.row].letters[tile.col].toggle();
+            }
+            ui.horizontal_wrapped(|ui| {
                 ui.style_mut().text_styles.insert(
                     egui::TextStyle::Button,
                     egui::FontId::new(20.0, eframe::epaint::FontFamily::Monospace),
@@ -128,31 +1498,68 @@ impl eframe::App for WordleSolve {
                 let download_button = egui::Button::new("Download");
 
                 if ui.add(download_button).on_hover_text("Download Words / Restart").clicked() {
-                    let _ = self.download();
+                    self.download(false);
                 }
 
                 let guess_button = egui::Button::new("Guess");
-                if ui.add(guess_button).on_hover_text("Guess the next word").clicked() && (self.guess != "" || self.guess_num == 0) {
-                    self.statistics.filters.clear();
-                    for word in self.board.words.iter() {
-                        if word.letters.iter().all(|letter| letter.get_state() == LetterState::Disabled) {
-                            continue;
-                        }
-                        self.statistics.filters.push(word.clone());
-                    }
+                if ui.add_enabled(!busy, guess_button).on_hover_text("Guess the next word").clicked() && (!self.guess.is_empty() || self.guess_num == 0) {
+                    self.update_filters();
                     if self.guess_num < MAX_ATTEMPTS {
-                        self.guess_num += 1;
-                        self.guess = self.statistics.guess();
-                        self.board.set_word(self.guess_num - 1, &self.guess);
-                        let word = self.board.words.iter_mut().nth(self.guess_num - 1).unwrap();
-                        for (idx, letter) in self.guess.chars().enumerate() {
-                            word.letters[idx].value = letter.clone();
-                            word.letters[idx].set_state(LetterState::Incorrect);
+                        if self.guess_num == 0 {
+                            self.load_openers();
                         }
+                        self.start_guess(self.guess_num);
+                    }
+                }
+                let undo_button = egui::Button::new("Undo");
+                let response = ui.add_enabled(self.history.can_undo(), undo_button).on_hover_ui(|ui| {
+                    if let Some(state) = self.history.last() {
+                        ui.label(format!("Ctrl+Z, back to {} candidates", state.candidates));
                     }
+                });
+                if response.clicked() {
+                    self.undo();
                 }
+
+                let redo_button = egui::Button::new("Redo");
+                let response = ui.add_enabled(self.history.can_redo(), redo_button).on_hover_ui(|ui| {
+                    if let Some(state) = self.history.next() {
+                        ui.label(format!("Ctrl+Shift+Z, forward to {} candidates", state.candidates));
+                    }
+                });
+                if response.clicked() {
+                    self.redo();
+                }
+
+                let blocklist_button = egui::Button::new("Rejected");
+                if ui.add(blocklist_button).on_hover_text("Review and restore rejected words").clicked() {
+                    self.show_blocklist = !self.show_blocklist;
+                }
+
+                let settings_button = egui::Button::new("Settings");
+                if ui.add(settings_button).on_hover_text("Strategy, past answers and word frequencies").clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+
+                let analyse_button = egui::Button::new("Analyse");
+                if ui.add_enabled(!busy && self.guess_num > 0, analyse_button).on_hover_text("Grade every guess against the solver").clicked() {
+                    self.start_analysis(self.board.clone());
+                }
+
+                let share_button = egui::Button::new("Share");
+                if ui.add_enabled(!busy, share_button).on_hover_text("Import or copy the board as coloured squares").clicked() {
+                    self.show_share = !self.show_share;
+                }
+
+                let host_button = egui::Button::new("Host");
+                if ui.add_enabled(!busy, host_button).on_hover_text("Play against the app, which hands out the colours for your guesses").clicked() {
+                    self.start_host();
+                }
+
                 let again_button = egui::Button::new("Play Again");
                 if ui.add(again_button).on_hover_text("Play again without redownloading dictionary").clicked() {
+                    self.cancel_guess();
+                    self.checkpoint();
                     self.statistics.filters.clear();
                     self.board = Board::default();
                     self.guess = "".to_string();
@@ -160,10 +1567,53 @@ impl eframe::App for WordleSolve {
                 }
 
             });
+            if let Some((_, task)) = &self.pending_guess {
+                ui.horizontal(|ui| {
+                    ui.style_mut().text_styles.insert(
+                        egui::TextStyle::Button,
+                        egui::FontId::new(20.0, eframe::epaint::FontFamily::Monospace),
+                    );
+                    ui.spinner();
+                    ui.label(format!("Thinking {:.0}%", task.progress().fraction() * 100.0));
+                    if ui.button("Cancel").on_hover_text("Play the best guess found so far").clicked() {
+                        task.progress().cancel();
+                    }
+                });
+            }
+            if self.pending_download.is_some() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Downloading words");
+                });
+            }
+            if !self.download_status.is_empty() {
+                ui.add(egui::Label::new(egui::RichText::new(self.download_status.as_str()).color(egui::Color32::RED)));
+            }
+            if self.pending_patterns.is_some() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Working out the feedback for every guess");
+                });
+            }
+            if !self.patterns_status.is_empty() {
+                ui.add(egui::Label::new(egui::RichText::new(self.patterns_status.as_str()).color(egui::Color32::RED)));
+            }
             let word_count = egui::Label::new("Words in Dictionary: ".to_string() + &self.statistics.len().to_string());
             ui.add(word_count);
+            if self.pending_checks.is_some() {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Checking the board");
+                });
+            }
+            if let Some(guarantee) = self.guarantee {
+                ui.add(egui::Label::new(guarantee.to_string()));
+            }
+            if let Some(endgame) = &self.endgame {
+                ui.add(egui::Label::new(format!("Endgame {}", endgame)));
+            }
             let mut end_text = "";
-            if self.guess == "" && self.guess_num > 0 {
+            if self.guess.is_empty() && self.guess_num > 0 {
                 end_text = if self.statistics.len() == 0 {
                     "No valid guesses left, Download Words First"
                 } else {
@@ -175,6 +1625,30 @@ impl eframe::App for WordleSolve {
             let guess_label = egui::Label::new(guess_text);
             ui.add(guess_label);
 
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Body,
+                egui::FontId::new(14.0, eframe::epaint::FontFamily::Monospace),
+            );
+            for conflict in conflicts.iter() {
+                ui.add(egui::Label::new(egui::RichText::new(conflict.to_string()).color(egui::Color32::RED)));
+            }
+            if let Some(correction) = &self.correction {
+                ui.horizontal(|ui| {
+                    ui.add(egui::Label::new(correction.to_string()));
+                    ui.style_mut().text_styles.insert(
+                        egui::TextStyle::Button,
+                        egui::FontId::new(14.0, eframe::epaint::FontFamily::Monospace),
+                    );
+                    if ui.add(egui::Button::new("Fix")).on_hover_text("Apply this correction").clicked() {
+                        apply_correction = true;
+                    }
+                });
+            }
+            ui.style_mut().text_styles.insert(
+                egui::TextStyle::Body,
+                egui::FontId::new(20.0, eframe::epaint::FontFamily::Monospace),
+            );
+
             ui.horizontal(|ui| {
                 let url_label = egui::Label::new("Dictionary Source URL:");
                 ui.add(url_label);
@@ -184,14 +1658,24 @@ impl eframe::App for WordleSolve {
                     egui::TextStyle::Body,
                     egui::FontId::new(14.0, eframe::epaint::FontFamily::Monospace),
                 );
-                let mut text_edit = egui::TextEdit::singleline(&mut self.words_url).desired_width(600.0);
-                ui.add(text_edit);
+                let text_edit = egui::TextEdit::singleline(&mut self.words_url).desired_width(600.0);
+                self.session_dirty |= ui.add(text_edit).changed();
             });
-            if reset {
-                self.statistics.remove_word(&self.guess);
-                self.guess = self.statistics.guess();
-                self.board.set_word(self.guess_num - 1, &self.guess);
+            if apply_correction {
+                if let Some(correction) = self.correction.take() {
+                    self.checkpoint();
+                    self.board.words[correction.tile.row].letters[correction.tile.col].set_state(correction.state);
+                }
+            }
+            if let Some(reason) = reset {
+                self.reject_guess(reason);
             }
         });
+        self.save_blocklist();
+    }
+
+    // catch anything changed since the last frame
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_session();
     }
 }
\ No newline at end of file
@@ -0,0 +1,126 @@
+use std::cmp::Reverse;
+use std::fmt;
+use super::{LetterState, PackedWord, Pattern, Position};
+
+// how many of the guesses leaving the host the fewest candidates are tried at each step
+pub const ABSURDLE_BRANCHES: usize = 10;
+// the longest winning sequence looked for
+pub const ABSURDLE_DEPTH: usize = 8;
+// how many positions the search may look at in total before giving up
+pub const ABSURDLE_BUDGET: usize = 5_000;
+
+// one guess of a game against the host, with the feedback it chose and how many words that left
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbsurdleStep {
+    pub guess: String,
+    pub pattern: Pattern,
+    pub remaining: usize,
+}
+
+impl fmt::Display for AbsurdleStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pattern.is_solved() {
+            write!(f, "{} {} solved", self.guess, self.pattern)
+        } else {
+            write!(f, "{} {} {} left", self.guess, self.pattern, self.remaining)
+        }
+    }
+}
+
+// a host that never picks an answer, every guess gets whichever feedback keeps the most words in
+// play, so the game is only won once a single word is left and it is guessed
+pub struct Absurdle;
+
+impl Absurdle {
+    // the feedback the host gives the guess and the candidates it keeps, the largest bucket with
+    // ties going to the fewest greens, then the fewest yellows
+    pub fn host(position: &Position, guess: &PackedWord) -> Option<(Pattern, Vec<usize>)> {
+        position.partition(guess).into_iter()
+            .min_by_key(|(pattern, bucket)| {
+                let states = pattern.states();
+                let count = |wanted: LetterState| states.iter().filter(|state| **state == wanted).count();
+                (Reverse(bucket.len()), count(LetterState::Correct), count(LetterState::Present), *pattern)
+            })
+    }
+
+    // whether this many candidates could still be won in the moves left, the last move has to be
+    // the only word left and every move before can split the words at most 243 ways
+    fn winnable(candidates: usize, moves: usize) -> bool {
+        match moves {
+            0 => false,
+            _ => (1..moves).fold(1usize, |most, _| most.saturating_mul(243)) >= candidates,
+        }
+    }
+
+    // the shortest sequence of guesses found that forces a win, trying longer sequences only once
+    // no shorter one wins
+    pub fn solve(position: &Position) -> Option<Vec<AbsurdleStep>> {
+        if position.candidates.is_empty() {
+            return None;
+        }
+        let mut budget = ABSURDLE_BUDGET;
+        (1..=ABSURDLE_DEPTH).find_map(|moves| Self::search(position, moves, &mut budget))
+    }
+
+    fn search(position: &Position, moves: usize, budget: &mut usize) -> Option<Vec<AbsurdleStep>> {
+        if !Self::winnable(position.candidates.len(), moves) || *budget == 0 {
+            return None;
+        }
+        *budget -= 1;
+        if let [answer] = position.candidates.as_slice() {
+            return Some(vec![AbsurdleStep { guess: answer.to_string(), pattern: Pattern::SOLVED, remaining: 1 }]);
+        }
+
+        let mut ranked: Vec<(usize, PackedWord, Pattern, Vec<usize>)> = position.guess_pool(position.pool_limit()).into_iter()
+            .filter_map(|guess| Self::host(position, &guess).map(|(pattern, bucket)| (bucket.len(), guess, pattern, bucket)))
+            .filter(|(remaining, _, _, _)| Self::winnable(*remaining, moves - 1))
+            .collect();
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        for (remaining, guess, pattern, bucket) in ranked.into_iter().take(ABSURDLE_BRANCHES) {
+            if let Some(rest) = Self::search(&position.narrow(&bucket), moves - 1, budget) {
+                let mut steps = vec![AbsurdleStep { guess: guess.to_string(), pattern, remaining }];
+                steps.extend(rest);
+                return Some(steps);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod absurdle_tests {
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    #[test]
+    fn host() {
+        let position = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        let (pattern, bucket) = Absurdle::host(&position, &word("bills")).unwrap();
+        assert_eq!(pattern.to_string(), ".gggg");
+        assert_eq!(bucket, vec![1, 2, 3, 4]);
+
+        // with every bucket the same size the host gives away as little as it can
+        let (pattern, bucket) = Absurdle::host(&position, &word("fbhkm")).unwrap();
+        assert_eq!(pattern.to_string(), ".y...");
+        assert_eq!(bucket, vec![0]);
+    }
+
+    #[test]
+    fn solve() {
+        let mut position = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        let result = Absurdle::solve(&position).unwrap();
+        assert_eq!(result.len(), 5);
+        assert_eq!(result[0].to_string(), "bills .gggg 4 left");
+        assert!(result[4].pattern.is_solved());
+
+        // a guess that tells every word apart leaves one word to guess
+        position.guesses.push(word("fbhkm"));
+        let result = Absurdle::solve(&position).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].guess, "fbhkm");
+        assert_eq!(result[0].remaining, 1);
+        assert_eq!(result[1].to_string(), format!("{} ggggg solved", result[1].guess));
+
+        assert_eq!(Absurdle::solve(&Position::default()), None);
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+use super::{storage, Commentary, MinExpectedGuesses, PackedWord, Position};
+
+const ANALYSIS_FILE: &str = "analysis.md";
+
+// one row of a finished game graded against the solver
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowAnalysis {
+    pub commentary: Commentary,
+    // the expected number of guesses to finish, counting this one, after the guess and after the
+    // solver's guess
+    pub expected_guesses: f64,
+    pub solver_expected_guesses: f64,
+}
+
+impl RowAnalysis {
+    pub fn new(commentary: Commentary, position: &Position) -> RowAnalysis {
+        let expected = |guess: &str| match PackedWord::new(guess) {
+            Some(guess) if !guess.letters().is_empty() => MinExpectedGuesses::expected_guesses(position, &guess),
+            _ => 0.0,
+        };
+        RowAnalysis {
+            expected_guesses: expected(&commentary.guess),
+            solver_expected_guesses: expected(&commentary.solver_guess),
+            commentary,
+        }
+    }
+
+    // how close the guess came to the solver's out of 100, a guess at least as good scores 100
+    pub fn skill(&self) -> f64 {
+        if self.expected_guesses <= 0.0 || self.solver_expected_guesses <= 0.0 {
+            return 100.0;
+        }
+        (100.0 * self.solver_expected_guesses / self.expected_guesses).min(100.0)
+    }
+
+    // how many more bits the feedback gave than the guess was expected to get, negative when the
+    // feedback was worse than average
+    pub fn luck(&self) -> f64 {
+        self.commentary.bits() - self.commentary.expected_bits
+    }
+}
+
+// every row of a finished game replayed and graded against the solver
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameAnalysis {
+    pub rows: Vec<RowAnalysis>,
+}
+
+impl GameAnalysis {
+    pub fn path() -> PathBuf {
+        storage::data_file(ANALYSIS_FILE)
+    }
+
+    pub fn save(&self, path: &PathBuf) -> std::io::Result<()> {
+        storage::write_file(path, &self.markdown())
+    }
+
+    // the average skill over every row
+    pub fn skill(&self) -> f64 {
+        if self.rows.is_empty() {
+            return 0.0;
+        }
+        self.rows.iter().map(|row| row.skill()).sum::<f64>() / self.rows.len() as f64
+    }
+
+    // the luck added up over every row, in bits
+    pub fn luck(&self) -> f64 {
+        self.rows.iter().map(|row| row.luck()).sum()
+    }
+
+    // the report as a Markdown table, one line per row of the board
+    pub fn markdown(&self) -> String {
+        let mut markdown = String::from("# Game Analysis\n\n");
+        markdown.push_str("| Row | Guess | Candidates | Bits | Expected Bits | Expected Guesses | Solver | Solver Bits | Solver Guesses | Skill | Luck |\n");
+        markdown.push_str("|---:|---|---|---:|---:|---:|---|---:|---:|---:|---:|\n");
+        for (idx, row) in self.rows.iter().enumerate() {
+            let commentary = &row.commentary;
+            let _ = writeln!(markdown, "| {} | {} | {} → {} | {:.2} | {:.2} | {:.2} | {} | {:.2} | {:.2} | {:.0} | {:+.2} |",
+                             idx + 1, commentary.guess, commentary.before, commentary.after, commentary.bits(),
+                             commentary.expected_bits, row.expected_guesses, commentary.solver_guess,
+                             commentary.solver_expected_bits, row.solver_expected_guesses, row.skill(), row.luck());
+        }
+        let _ = writeln!(markdown, "\nSkill {:.0} out of 100, luck {:+.2} bits", self.skill(), self.luck());
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod analysis_tests {
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    fn commentary(guess: &str, before: usize, after: usize, expected_bits: f64) -> Commentary {
+        Commentary {
+            guess: guess.to_string(),
+            before,
+            after,
+            before_weight: before as f64,
+            after_weight: after as f64,
+            expected_bits,
+            solver_guess: String::from("fbhmz"),
+            solver_expected_bits: 2.0,
+        }
+    }
+
+    #[test]
+    fn row() {
+        let mut position = Position::uniform(&["bills", "fills", "hills", "mills"]);
+        position.guesses.push(word("fbhmz"));
+        let result = RowAnalysis::new(commentary("bills", 4, 3, 0.8113), &position);
+        assert_eq!(result.solver_expected_guesses, 2.0);
+        assert!(result.expected_guesses > 2.0);
+        assert!(result.skill() < 100.0);
+        // three of four left is a little worse than expected
+        assert!(result.luck() < 0.0);
+
+        let result = RowAnalysis::new(commentary("fbhmz", 4, 1, 2.0), &position);
+        assert_eq!(result.skill(), 100.0);
+        assert_eq!(result.luck(), 0.0);
+    }
+
+    #[test]
+    fn markdown() {
+        let position = Position::uniform(&["bills", "fills", "hills", "mills", "fbhmz"]);
+        let result = GameAnalysis { rows: vec![RowAnalysis::new(commentary("fbhmz", 4, 1, 2.0), &position)] };
+        assert_eq!(result.skill(), 100.0);
+        let markdown = result.markdown();
+        assert!(markdown.starts_with("# Game Analysis\n"));
+        assert!(markdown.contains("| 1 | fbhmz | 4 → 1 | 2.00 | 2.00 |"));
+        assert!(markdown.ends_with("Skill 100 out of 100, luck +0.00 bits\n"));
+        assert_eq!(GameAnalysis::default().skill(), 0.0);
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use super::storage;
+
+pub const BLOCKLIST_FILE: &str = "blocklist.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RejectReason {
+    // the game won't take the word, so it can't be the answer either
+    #[default]
+    NotAccepted,
+    // the word could still be the answer, it just shouldn't be suggested
+    Unwanted,
+}
+
+impl RejectReason {
+    fn name(&self) -> &'static str {
+        match self {
+            RejectReason::NotAccepted => "not-accepted",
+            RejectReason::Unwanted => "unwanted",
+        }
+    }
+
+    fn parse(name: &str) -> Option<RejectReason> {
+        match name {
+            "not-accepted" => Some(RejectReason::NotAccepted),
+            "unwanted" => Some(RejectReason::Unwanted),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            RejectReason::NotAccepted => "not accepted",
+            RejectReason::Unwanted => "unwanted",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+// rejected words and why, stored one word per line as "word reason"
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Blocklist {
+    entries: BTreeMap<String, RejectReason>,
+}
+
+impl Blocklist {
+    pub fn parse(content: &str) -> Blocklist {
+        let mut blocklist = Blocklist::default();
+        for line in content.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(word) = fields.next() else {
+                continue;
+            };
+            // a bare word is treated as one the game doesn't accept
+            let reason = fields.next().and_then(RejectReason::parse).unwrap_or_default();
+            blocklist.insert(word, reason);
+        }
+        blocklist
+    }
+
+    pub fn path() -> PathBuf {
+        storage::data_file(BLOCKLIST_FILE)
+    }
+
+    // a missing file is just an empty blocklist
+    pub fn load(path: &PathBuf) -> std::io::Result<Blocklist> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Blocklist::parse(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Blocklist::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &PathBuf) -> std::io::Result<()> {
+        storage::write_file(path, &self.to_string())
+    }
+
+    pub fn insert(&mut self, word: &str, reason: RejectReason) {
+        self.entries.insert(word.to_string(), reason);
+    }
+
+    pub fn remove(&mut self, word: &str) {
+        self.entries.remove(word);
+    }
+
+    pub fn get(&self, word: &str) -> Option<RejectReason> {
+        self.entries.get(word).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // every entry in alphabetical order
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &RejectReason)> {
+        self.entries.iter()
+    }
+}
+
+impl fmt::Display for Blocklist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (word, reason) in self.entries.iter() {
+            writeln!(f, "{} {}", word, reason.name())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod blocklist_tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        let result: Blocklist = Default::default();
+        assert!(result.is_empty());
+        assert_eq!(RejectReason::default(), RejectReason::NotAccepted);
+    }
+
+    #[test]
+    fn parse() {
+        let result = Blocklist::parse("xylyl unwanted\n\nqajaq not-accepted\nzzzzz\n");
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.get("xylyl"), Some(RejectReason::Unwanted));
+        assert_eq!(result.get("qajaq"), Some(RejectReason::NotAccepted));
+        assert_eq!(result.get("zzzzz"), Some(RejectReason::NotAccepted));
+        assert_eq!(result.get("crane"), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut result: Blocklist = Default::default();
+        result.insert("xylyl", RejectReason::Unwanted);
+        result.insert("qajaq", RejectReason::NotAccepted);
+        assert_eq!(result.to_string(), "qajaq not-accepted\nxylyl unwanted\n");
+        assert_eq!(Blocklist::parse(&result.to_string()), result);
+
+        result.remove("xylyl");
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn save_load() {
+        let path = std::env::temp_dir().join("wordle_solve_blocklist_tests").join(BLOCKLIST_FILE);
+        assert_eq!(Blocklist::load(&path).unwrap(), Blocklist::default());
+
+        let mut result: Blocklist = Default::default();
+        result.insert("xylyl", RejectReason::Unwanted);
+        result.save(&path).unwrap();
+        assert_eq!(Blocklist::load(&path).unwrap(), result);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,7 +1,7 @@
 use std::default::Default;
 use super::{Word};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Board {
     pub words: Vec<Word>,
 }
@@ -9,9 +9,17 @@ pub struct Board {
 pub const MAX_ATTEMPTS: usize = 6;
 
 impl Board {
-    pub fn set_word(self: &mut Self, index: usize,  word: &String,) {
-        if index >= MAX_ATTEMPTS {
-            println!("Index {} is greater than allowed length {}", index, MAX_ATTEMPTS)
+    // a board with room for more attempts than a single game, for playing several boards at once
+    pub fn with_rows(rows: usize) -> Board {
+        Board {
+            words: vec![Default::default(); rows],
+        }
+    }
+
+    #[allow(clippy::needless_arbitrary_self_type)]
+    pub fn set_word(self: &mut Self, index: usize,  word: &str,) {
+        if index >= self.words.len() {
+            println!("Index {} is greater than allowed length {}", index, self.words.len())
         } else {
             self.words[index].set(word);
             self.words[index].letters.iter_mut().for_each( |letter| letter.set_state(crate::wordle::LetterState::Incorrect));
@@ -21,9 +29,7 @@ impl Board {
 
 impl Default for Board {
     fn default() -> Self {
-        Board {
-            words: vec![Default::default(); MAX_ATTEMPTS],
-        }
+        Board::with_rows(MAX_ATTEMPTS)
     }
 }
 
@@ -34,6 +40,7 @@ mod letter_tests {
     use super::super::super::{MAX_LETTERS};
 
     #[test]
+    #[allow(clippy::iter_count)]
     fn default() {
         let result: Board = Default::default();
         assert_eq!(result.words.iter().count(), MAX_ATTEMPTS);
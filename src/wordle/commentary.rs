@@ -0,0 +1,99 @@
+use std::fmt;
+use super::{PackedWord, Position};
+
+// how one guess of a game compares with what the solver would have played in its place
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commentary {
+    pub guess: String,
+    // the candidates before and after the guess
+    pub before: usize,
+    pub after: usize,
+    // how likely the candidates before and after the guess were to be the answer, all together
+    pub before_weight: f64,
+    pub after_weight: f64,
+    // how much the guess was expected to narrow the candidates down, in bits
+    pub expected_bits: f64,
+    // the solver's guess from the same position, empty when it had none
+    pub solver_guess: String,
+    pub solver_expected_bits: f64,
+}
+
+impl Commentary {
+    // the information the feedback for the guess gives on average, weighting each answer by how
+    // likely it is
+    pub fn expected_bits(position: &Position, guess: &PackedWord) -> f64 {
+        let total = position.total_weight();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        position.partition(guess).values()
+            .map(|bucket| bucket.iter().map(|idx| position.weights[*idx]).sum::<f64>() / total)
+            .filter(|probability| *probability > 0.0)
+            .map(|probability| -probability * probability.log2())
+            .sum()
+    }
+
+    // how much the feedback the guess actually got narrowed the candidates down, in bits, weighted
+    // the same way as the expected bits
+    pub fn bits(&self) -> f64 {
+        if self.before_weight <= 0.0 || self.after_weight <= 0.0 {
+            return 0.0;
+        }
+        (self.before_weight / self.after_weight).log2()
+    }
+}
+
+impl fmt::Display for Commentary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} to {} words, {:.2} bits (expected {:.2})", self.guess, self.before, self.after,
+               self.bits(), self.expected_bits)?;
+        if self.solver_guess.is_empty() {
+            Ok(())
+        } else if self.solver_guess == self.guess {
+            write!(f, ", the solver's guess too")
+        } else {
+            write!(f, ", solver: {} (expected {:.2})", self.solver_guess, self.solver_expected_bits)
+        }
+    }
+}
+
+#[cfg(test)]
+mod commentary_tests {
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    #[test]
+    fn expected_bits() {
+        let position = Position::uniform(&["bills", "fills", "hills", "mills"]);
+        assert_eq!(Commentary::expected_bits(&position, &word("fbhmz")), 2.0);
+        // one word is told apart from the other three
+        let bits = Commentary::expected_bits(&position, &word("bills"));
+        assert!((bits - 0.8113).abs() < 0.001);
+        assert_eq!(Commentary::expected_bits(&Position::default(), &word("bills")), 0.0);
+    }
+
+    #[test]
+    fn display() {
+        let mut result = Commentary {
+            guess: String::from("bills"),
+            before: 4,
+            after: 1,
+            before_weight: 4.0,
+            after_weight: 1.0,
+            expected_bits: 0.8113,
+            solver_guess: String::from("fbhmz"),
+            solver_expected_bits: 2.0,
+        };
+        assert_eq!(result.bits(), 2.0);
+        assert_eq!(result.to_string(), "bills: 4 to 1 words, 2.00 bits (expected 0.81), solver: fbhmz (expected 2.00)");
+        result.solver_guess = String::from("bills");
+        assert_eq!(result.to_string(), "bills: 4 to 1 words, 2.00 bits (expected 0.81), the solver's guess too");
+
+        // leaving only the likeliest answer tells little
+        result.before_weight = 103.0;
+        result.after_weight = 100.0;
+        assert!((result.bits() - 0.0426).abs() < 0.001);
+        result.after_weight = 0.0;
+        assert_eq!(result.bits(), 0.0);
+    }
+}
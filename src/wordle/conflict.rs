@@ -0,0 +1,221 @@
+use std::fmt;
+use super::{LetterState, Word, MAX_LETTERS};
+
+// a single square on the board, rows and columns are zero based
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictKind {
+    // two different letters are green in the same column
+    ConflictingGreens,
+    // a row has the known green letter in its column but does not mark it green
+    GreenNotGreen,
+    // a letter is green or yellow in one row and absent in another
+    PresentAndAbsent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub letter: char,
+    // the tile that established what is known and the tile that disagrees with it
+    pub tiles: Vec<Tile>,
+}
+
+// a single tile change that leaves at least one candidate word
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correction {
+    pub tile: Tile,
+    pub state: LetterState,
+    pub candidates: usize,
+}
+
+impl Conflict {
+    // find every contradiction between the rows of feedback, the rows are expected in board order
+    pub fn find(rows: &[Word]) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+
+        for (row_a, word_a) in rows.iter().enumerate() {
+            for (col, letter_a) in word_a.letters.iter().enumerate() {
+                if letter_a.get_state() != LetterState::Correct {
+                    continue;
+                }
+                for (row_b, word_b) in rows.iter().enumerate() {
+                    if row_b == row_a {
+                        continue;
+                    }
+                    let letter_b = &word_b.letters[col];
+                    let tiles = vec![Tile { row: row_a, col }, Tile { row: row_b, col }];
+                    // only report clashing greens once, from the earlier row
+                    if letter_b.get_state() == LetterState::Correct && letter_b.value != letter_a.value {
+                        if row_b > row_a {
+                            conflicts.push(Conflict { kind: ConflictKind::ConflictingGreens, letter: letter_a.value, tiles });
+                        }
+                    } else if letter_b.value == letter_a.value && letter_b.get_state() != LetterState::Correct {
+                        conflicts.push(Conflict { kind: ConflictKind::GreenNotGreen, letter: letter_a.value, tiles });
+                    }
+                }
+            }
+        }
+
+        for (row_a, word_a) in rows.iter().enumerate() {
+            for (col_a, letter_a) in word_a.letters.iter().enumerate() {
+                let state = letter_a.get_state();
+                if state != LetterState::Correct && state != LetterState::Present {
+                    continue;
+                }
+                for (row_b, word_b) in rows.iter().enumerate() {
+                    if row_b == row_a || Self::marked_present(word_b, letter_a.value) {
+                        continue;
+                    }
+                    if let Some(col_b) = word_b.letters.iter().position(|letter| letter.value == letter_a.value) {
+                        // a green that was already reported against this row doesn't need reporting twice
+                        let tiles = vec![Tile { row: row_a, col: col_a }, Tile { row: row_b, col: col_b }];
+                        if conflicts.iter().any(|conflict| conflict.tiles == tiles) {
+                            continue;
+                        }
+                        conflicts.push(Conflict { kind: ConflictKind::PresentAndAbsent, letter: letter_a.value, tiles });
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    fn marked_present(word: &Word, value: char) -> bool {
+        word.letters.iter().any(|letter| {
+            letter.value == value && (letter.get_state() == LetterState::Correct || letter.get_state() == LetterState::Present)
+        })
+    }
+
+    // every alternative state for every tile, conflicting tiles first so they win ties
+    pub fn corrections(rows: &[Word], conflicts: &[Conflict]) -> Vec<(Tile, LetterState)> {
+        let mut tiles: Vec<Tile> = Vec::new();
+        for conflict in conflicts.iter() {
+            for tile in conflict.tiles.iter() {
+                if !tiles.contains(tile) {
+                    tiles.push(*tile);
+                }
+            }
+        }
+        for row in 0..rows.len() {
+            for col in 0..MAX_LETTERS {
+                let tile = Tile { row, col };
+                if !tiles.contains(&tile) {
+                    tiles.push(tile);
+                }
+            }
+        }
+
+        let mut ret = Vec::new();
+        for tile in tiles {
+            let current = rows[tile.row].letters[tile.col].get_state();
+            for state in [LetterState::Correct, LetterState::Present, LetterState::Incorrect] {
+                if state != current {
+                    ret.push((tile, state));
+                }
+            }
+        }
+        ret
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let first = self.tiles[0];
+        let second = self.tiles[1];
+        let description = match self.kind {
+            ConflictKind::ConflictingGreens => "a different letter is green in the same column at",
+            ConflictKind::GreenNotGreen => "it is not green in the same column at",
+            ConflictKind::PresentAndAbsent => "it is marked absent at",
+        };
+        write!(f, "'{}' at row {} col {} is in the word, but {} row {} col {}",
+               self.letter, first.row + 1, first.col + 1, description, second.row + 1, second.col + 1)
+    }
+}
+
+impl fmt::Display for Correction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self.state {
+            LetterState::Correct => "green",
+            LetterState::Present => "yellow",
+            _ => "absent",
+        };
+        write!(f, "Try marking row {} col {} {} ({} candidates)", self.tile.row + 1, self.tile.col + 1, state, self.candidates)
+    }
+}
+
+#[cfg(test)]
+mod conflict_tests {
+    use super::*;
+
+    fn word(value: &str, states: &str) -> Word {
+        let mut word: Word = Default::default();
+        word.set(value);
+        for (idx, state) in states.chars().enumerate() {
+            word.letters[idx].set_state(match state {
+                'g' => LetterState::Correct,
+                'y' => LetterState::Present,
+                _ => LetterState::Incorrect,
+            });
+        }
+        word
+    }
+
+    #[test]
+    fn consistent() {
+        let rows = vec![word("crane", "..y.g"), word("about", "y....")];
+        assert_eq!(Conflict::find(&rows), vec![]);
+    }
+
+    #[test]
+    fn green_and_absent() {
+        let rows = vec![word("crane", "....g"), word("those", ".....")];
+        let conflicts = Conflict::find(&rows);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::GreenNotGreen);
+        assert_eq!(conflicts[0].letter, 'e');
+        assert_eq!(conflicts[0].tiles, vec![Tile { row: 0, col: 4 }, Tile { row: 1, col: 4 }]);
+    }
+
+    #[test]
+    fn yellow_on_green() {
+        let rows = vec![word("bland", "..y.."), word("crane", "..g..")];
+        let conflicts = Conflict::find(&rows);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::GreenNotGreen);
+        assert_eq!(conflicts[0].tiles, vec![Tile { row: 1, col: 2 }, Tile { row: 0, col: 2 }]);
+    }
+
+    #[test]
+    fn conflicting_greens() {
+        let rows = vec![word("crane", "g...."), word("slate", "g....")];
+        let conflicts = Conflict::find(&rows);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::ConflictingGreens);
+    }
+
+    #[test]
+    fn present_and_absent() {
+        let rows = vec![word("crane", "y...."), word("thick", ".....")];
+        let conflicts = Conflict::find(&rows);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::PresentAndAbsent);
+        assert_eq!(conflicts[0].tiles, vec![Tile { row: 0, col: 0 }, Tile { row: 1, col: 3 }]);
+    }
+
+    #[test]
+    fn corrections() {
+        let rows = vec![word("crane", "....g"), word("those", ".....")];
+        let conflicts = Conflict::find(&rows);
+        let corrections = Conflict::corrections(&rows, &conflicts);
+        assert_eq!(corrections.len(), 2 * 2 * MAX_LETTERS);
+        assert_eq!(corrections[0], (Tile { row: 0, col: 4 }, LetterState::Present));
+        assert_eq!(corrections[2], (Tile { row: 1, col: 4 }, LetterState::Correct));
+    }
+}
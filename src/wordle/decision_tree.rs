@@ -0,0 +1,333 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use super::{partition, storage, Board, LetterState, Pattern, Statistics, Word, MAX_ATTEMPTS, MAX_LETTERS};
+
+pub const DECISION_TREE_FILE: &str = "tree.txt";
+
+// the next guess for every feedback the guesses so far could get, so nothing needs working out
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecisionTree {
+    root: Option<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    guess: String,
+    // whether the guess could be the answer, which ends a path through the tree
+    answer: bool,
+    children: BTreeMap<Pattern, Node>,
+}
+
+impl Node {
+    fn new(guess: &str) -> Node {
+        Node { guess: guess.to_string(), answer: false, children: BTreeMap::new() }
+    }
+}
+
+// how well a tree solves a list of answers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Verification {
+    pub answers: usize,
+    pub max_guesses: usize,
+    pub total_guesses: usize,
+    pub errors: Vec<String>,
+}
+
+impl Verification {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn average(&self) -> f64 {
+        self.total_guesses as f64 / (self.answers - self.errors.len()).max(1) as f64
+    }
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            write!(f, "Solves all {} answers, {:.3} guesses on average and at most {}", self.answers, self.average(), self.max_guesses)
+        } else {
+            write!(f, "{} of {} answers aren't solved: {}", self.errors.len(), self.answers, self.errors.join(", "))
+        }
+    }
+}
+
+impl DecisionTree {
+    // play out every feedback the strategy's guesses could get, as far as the attempts allow
+    pub fn build(statistics: &Statistics) -> DecisionTree {
+        let mut statistics = statistics.clone();
+        statistics.set_tree(DecisionTree::default());
+        let mut board = Board::default();
+        DecisionTree { root: Self::build_node(&mut statistics, &mut board, 0) }
+    }
+
+    fn build_node(statistics: &mut Statistics, board: &mut Board, row: usize) -> Option<Node> {
+        statistics.set_board(board);
+        let guess = statistics.guess();
+        if guess.is_empty() {
+            return None;
+        }
+        let candidates = statistics.candidates();
+        let mut node = Node::new(&guess);
+        node.answer = candidates.contains(&guess);
+        if row + 1 >= MAX_ATTEMPTS {
+            return Some(node);
+        }
+        for (pattern, _) in partition(&guess, &candidates) {
+            if pattern.is_solved() {
+                continue;
+            }
+            board.words[row] = Word::feedback(&guess, pattern);
+            if let Some(child) = Self::build_node(statistics, board, row + 1) {
+                node.children.insert(pattern, child);
+            }
+            board.words[row] = Word::default();
+        }
+        Some(node)
+    }
+
+    // one answer per line, the guesses made for it with their feedback ending in the answer, either
+    // as crane:..y.g pairs or as a word followed by its feedback in B, Y and G, optionally numbered
+    pub fn parse(content: &str) -> Result<DecisionTree, Box<dyn Error>> {
+        let mut tree = DecisionTree::default();
+        for (idx, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let steps = Self::parse_line(line).map_err(|e| format!("line {}: {}", idx + 1, e))?;
+            tree.insert(&steps).map_err(|e| format!("line {}: {}", idx + 1, e))?;
+        }
+        Ok(tree)
+    }
+
+    fn parse_line(line: &str) -> Result<Vec<(String, Pattern)>, String> {
+        let mut steps = Vec::new();
+        let mut tokens = line.split_whitespace();
+        while let Some(token) = tokens.next() {
+            let (guess, feedback) = match token.split_once(':') {
+                Some((guess, feedback)) => (guess, feedback.to_string()),
+                None => {
+                    let feedback = tokens.next().ok_or(format!("'{}' has no feedback", token))?;
+                    (token, feedback.trim_end_matches(|c: char| c.is_ascii_digit()).to_string())
+                }
+            };
+            let guess = guess.to_lowercase();
+            if guess.chars().count() != MAX_LETTERS || !guess.chars().all(|c| c.is_alphabetic()) {
+                return Err(format!("'{}' isn't a {} letter word", guess, MAX_LETTERS));
+            }
+            steps.push((guess, Self::parse_feedback(&feedback)?));
+        }
+        let Some((answer, last)) = steps.last() else {
+            return Err(String::from("no guesses"));
+        };
+        if !last.is_solved() {
+            return Err(format!("doesn't end with the answer, {} got {}", answer, last));
+        }
+        for (guess, pattern) in steps.iter() {
+            if Pattern::new(guess, answer) != *pattern {
+                return Err(format!("{} would get {} rather than {} if the answer is {}", guess, Pattern::new(guess, answer), pattern, answer));
+            }
+        }
+        Ok(steps)
+    }
+
+    fn parse_feedback(feedback: &str) -> Result<Pattern, String> {
+        if feedback.chars().count() != MAX_LETTERS {
+            return Err(format!("feedback '{}' needs {} characters", feedback, MAX_LETTERS));
+        }
+        let states = feedback.chars().map(|c| match c.to_ascii_lowercase() {
+            'g' => Ok(LetterState::Correct),
+            'y' => Ok(LetterState::Present),
+            '.' | 'b' => Ok(LetterState::Incorrect),
+            _ => Err(format!("unknown feedback '{}' in '{}'", c, feedback)),
+        }).collect::<Result<Vec<LetterState>, String>>()?;
+        Ok(Pattern::from_states(&states))
+    }
+
+    fn insert(&mut self, steps: &[(String, Pattern)]) -> Result<(), String> {
+        let Some((first, _)) = steps.first() else {
+            return Ok(());
+        };
+        let mut node = self.root.get_or_insert_with(|| Node::new(first));
+        for (idx, (guess, pattern)) in steps.iter().enumerate() {
+            if node.guess != *guess {
+                return Err(format!("guesses {} where another line guesses {}", guess, node.guess));
+            }
+            let Some((next, _)) = steps.get(idx + 1) else {
+                node.answer = true;
+                break;
+            };
+            node = node.children.entry(*pattern).or_insert_with(|| Node::new(next));
+        }
+        Ok(())
+    }
+
+    pub fn path() -> PathBuf {
+        storage::data_file(DECISION_TREE_FILE)
+    }
+
+    pub fn load(path: &PathBuf) -> Result<DecisionTree, Box<dyn Error>> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: &PathBuf) -> std::io::Result<()> {
+        storage::write_file(path, &self.to_string())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    // how many answers the tree has a path to
+    pub fn len(&self) -> usize {
+        fn count(node: &Node) -> usize {
+            node.answer as usize + node.children.values().map(count).sum::<usize>()
+        }
+        self.root.as_ref().map_or(0, count)
+    }
+
+    // the guess the tree makes after the given rows, if it has one
+    pub fn guess(&self, rows: &[Word]) -> Option<&str> {
+        let mut node = self.root.as_ref()?;
+        for row in rows {
+            if row.value() != node.guess {
+                return None;
+            }
+            node = node.children.get(&row.pattern())?;
+        }
+        Some(&node.guess)
+    }
+
+    // play every answer through the tree, only allowing guesses from the given words
+    pub fn verify(&self, answers: &[String], guesses: &HashSet<String>) -> Verification {
+        let mut verification = Verification { answers: answers.len(), ..Default::default() };
+        for answer in answers {
+            match self.play(answer, guesses) {
+                Ok(count) => {
+                    verification.total_guesses += count;
+                    verification.max_guesses = verification.max_guesses.max(count);
+                }
+                Err(e) => verification.errors.push(format!("{} {}", answer, e)),
+            }
+        }
+        verification
+    }
+
+    fn play(&self, answer: &str, guesses: &HashSet<String>) -> Result<usize, String> {
+        let mut node = self.root.as_ref().ok_or("has no guesses")?;
+        for count in 1..=MAX_ATTEMPTS {
+            if !guesses.contains(&node.guess) {
+                return Err(format!("is guessed with {} which isn't allowed", node.guess));
+            }
+            let pattern = Pattern::new(&node.guess, answer);
+            if pattern.is_solved() {
+                return Ok(count);
+            }
+            node = node.children.get(&pattern).ok_or(format!("has no guess after {}:{}", node.guess, pattern))?;
+        }
+        Err(format!("isn't solved in {} guesses", MAX_ATTEMPTS))
+    }
+
+    fn write_paths(node: &Node, path: &mut Vec<String>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if node.answer {
+            let solved = format!("{}:{}", node.guess, Pattern::SOLVED);
+            writeln!(f, "{}", path.iter().chain(std::iter::once(&solved)).cloned().collect::<Vec<String>>().join(" "))?;
+        }
+        for (pattern, child) in node.children.iter() {
+            path.push(format!("{}:{}", node.guess, pattern));
+            Self::write_paths(child, path, f)?;
+            path.pop();
+        }
+        Ok(())
+    }
+}
+
+// one line per answer the tree can reach, in the same form it's parsed from
+impl fmt::Display for DecisionTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.root {
+            Some(root) => Self::write_paths(root, &mut Vec::new(), f),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod decision_tree_tests {
+    use crate::wordle::StrategyKind;
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let result = DecisionTree::parse("crane:..g.g slate:ggggg\n# a comment\n\nCRANE BBBBB1 fills GGGGG2\ncrane:ggggg").unwrap();
+        assert_eq!(result.guess(&[]), Some("crane"));
+        assert_eq!(result.guess(&[Word::feedback("crane", Pattern::new("crane", "slate"))]), Some("slate"));
+        assert_eq!(result.guess(&[Word::feedback("crane", Pattern::new("crane", "fills"))]), Some("fills"));
+        assert_eq!(result.guess(&[Word::feedback("slate", Pattern::SOLVED)]), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        // the feedback doesn't match the answer
+        assert!(DecisionTree::parse("crane:..... slate:ggggg").is_err());
+        // two different guesses after the same feedback
+        assert!(DecisionTree::parse("crane:..g.g slate:ggggg\ncrane:..g.g plate:..... slate:ggggg").is_err());
+        assert!(DecisionTree::parse("crane:..g.g").is_err());
+        assert!(DecisionTree::parse("crane:..x.g slate:ggggg").is_err());
+        assert!(DecisionTree::parse("cran:ggggg").is_err());
+        assert!(DecisionTree::parse("crane").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let content = "crane:ggggg\ncrane:..g.g slate:ggggg\ncrane:..g.g slate:.gggg plate:ggggg\n";
+        let result = DecisionTree::parse(content).unwrap();
+        assert_eq!(result.to_string(), content);
+        assert_eq!(result.len(), 3);
+        assert_eq!(DecisionTree::parse(&result.to_string()).unwrap(), result);
+    }
+
+    #[test]
+    fn build() {
+        let mut statistics = Statistics::default();
+        statistics.add_words("bills\nfills\nhills\nkills\nmills");
+        let result = DecisionTree::build(&statistics);
+        let answers = statistics.candidates();
+        let verification = result.verify(&answers, &answers.iter().cloned().collect());
+        assert!(verification.is_valid(), "{}", verification);
+        assert_eq!(verification.answers, 5);
+        assert_eq!(verification.max_guesses, 5);
+        assert_eq!(result.to_string().lines().count(), 5);
+
+        // a guess that can't be the answer isn't written as a path of its own
+        statistics.add_word("fbhkm");
+        statistics.set_answers(&answers);
+        statistics.set_strategy(StrategyKind::MinExpectedGuesses);
+        let result = DecisionTree::build(&statistics);
+        assert_eq!(result.guess(&[]), Some("fbhkm"));
+        assert_eq!(result.len(), 5);
+        assert_eq!(DecisionTree::parse(&result.to_string()).unwrap(), result);
+    }
+
+    #[test]
+    fn verify() {
+        let result = DecisionTree::parse("crane:..g.g slate:ggggg").unwrap();
+        let answers = words(&["slate", "plate"]);
+        let guesses: HashSet<String> = words(&["crane", "slate", "plate"]).into_iter().collect();
+        let verification = result.verify(&answers, &guesses);
+        assert_eq!(verification.errors.len(), 1);
+        assert!(verification.errors[0].starts_with("plate"));
+
+        let verification = result.verify(&words(&["slate"]), &words(&["slate"]).into_iter().collect());
+        assert!(!verification.is_valid());
+        assert!(DecisionTree::default().verify(&answers, &guesses).errors.len() == 2);
+    }
+}
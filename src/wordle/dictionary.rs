@@ -0,0 +1,31 @@
+use std::error::Error;
+
+pub const DEFAULT_DICTIONARY_URL: &str = "https://raw.githubusercontent.com/tabatkins/wordle-list/main/words";
+
+// read a word list from a url or, for anything that isn't one, a local file
+pub fn load_source(source: &str) -> Result<String, Box<dyn Error>> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = reqwest::blocking::get(source)?;
+        Ok(response.text()?)
+    } else {
+        Ok(std::fs::read_to_string(source)?)
+    }
+}
+
+#[cfg(test)]
+mod dictionary_tests {
+    use super::*;
+
+    #[test]
+    fn load_file() {
+        let path = std::env::temp_dir().join("wordle_solve_dictionary_tests.txt");
+        std::fs::write(&path, "never\ngonna\n").unwrap();
+        assert_eq!(load_source(path.to_str().unwrap()).unwrap(), "never\ngonna\n");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn load_missing_file() {
+        assert!(load_source("/does/not/exist/words.txt").is_err());
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::cmp::Ordering;
+use std::fmt;
+use super::{PackedWord, Pattern, Position, Progress, Strategy};
+
+// the most candidates the exact search takes over at unless configured otherwise
+pub const DEFAULT_ENDGAME_THRESHOLD: usize = 50;
+
+// how many guesses a lost game counts as costing beyond the attempts it used
+const FAILURE_COST: f64 = 1.0;
+// roughly how many guess and answer pairs the search may compare before giving up, sets of
+// candidates that differ in a single letter can take far longer than usual to search
+pub const ENDGAME_BUDGET: usize = 10_000_000;
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EndgameGoal {
+    // the fewest guesses on average, a lost game counting as one more guess than the attempts allow
+    #[default]
+    FewestGuesses,
+    // the best chance of solving within the attempts left, then the fewest guesses
+    MostSolves,
+}
+
+impl EndgameGoal {
+    pub const ALL: [EndgameGoal; 2] = [EndgameGoal::FewestGuesses, EndgameGoal::MostSolves];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EndgameGoal::FewestGuesses => "guesses",
+            EndgameGoal::MostSolves => "solves",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<EndgameGoal> {
+        Self::ALL.into_iter().find(|goal| goal.name() == name)
+    }
+
+    // the better outcome first
+    fn order(&self, a: &Outcome, b: &Outcome) -> Ordering {
+        let guesses = a.expected_guesses.total_cmp(&b.expected_guesses);
+        let solves = b.solve_probability.total_cmp(&a.solve_probability);
+        match self {
+            EndgameGoal::FewestGuesses => guesses.then(solves),
+            EndgameGoal::MostSolves => solves.then(guesses),
+        }
+    }
+
+    fn better(&self, a: &Outcome, b: &Outcome) -> bool {
+        let fewer_guesses = a.expected_guesses < b.expected_guesses - EPSILON;
+        let same_guesses = (a.expected_guesses - b.expected_guesses).abs() <= EPSILON;
+        let more_solves = a.solve_probability > b.solve_probability + EPSILON;
+        let same_solves = (a.solve_probability - b.solve_probability).abs() <= EPSILON;
+        match self {
+            EndgameGoal::FewestGuesses => fewer_guesses || (same_guesses && more_solves),
+            EndgameGoal::MostSolves => more_solves || (same_solves && fewer_guesses),
+        }
+    }
+}
+
+impl fmt::Display for EndgameGoal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            EndgameGoal::FewestGuesses => "Fewest Guesses",
+            EndgameGoal::MostSolves => "Most Solves",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outcome {
+    // counting the guess about to be made
+    pub expected_guesses: f64,
+    pub solve_probability: f64,
+}
+
+impl Outcome {
+    const FAILED: Outcome = Outcome { expected_guesses: FAILURE_COST, solve_probability: 0.0 };
+    const SOLVED: Outcome = Outcome { expected_guesses: 1.0, solve_probability: 1.0 };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndgameMove {
+    pub guess: String,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for EndgameMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:.2} guesses expected, {:.0}% chance to solve",
+               self.guess, self.outcome.expected_guesses, self.outcome.solve_probability * 100.0)
+    }
+}
+
+// an exhaustive search over every allowed guess, only practical for a handful of candidates, it
+// gives no move at all rather than an inexact one when the search is too big, cancelling it gives
+// back the best guess it has finished working out
+pub struct Endgame {
+    pub goal: EndgameGoal,
+}
+
+struct Search<'a> {
+    goal: EndgameGoal,
+    weights: &'a [f64],
+    // the feedback for every guess against every candidate, candidates come first in the guesses
+    patterns: Vec<Vec<Pattern>>,
+    memo: HashMap<(Vec<usize>, usize), (Outcome, usize)>,
+    work: usize,
+    progress: &'a Progress,
+    // whether a first guess has been fully worked out, cancelling only stops the search after that
+    // so there's always a move to give back
+    settled: bool,
+}
+
+// a guess along with the groups of candidates it splits them into and the best it could possibly do
+struct Split {
+    guess: usize,
+    buckets: Vec<(Pattern, Vec<usize>)>,
+    bound: Outcome,
+}
+
+impl<'a> Search<'a> {
+    fn new(goal: EndgameGoal, position: &'a Position, guesses: &[&PackedWord], progress: &'a Progress) -> Search<'a> {
+        let patterns = guesses.iter()
+            .map(|guess| position.patterns(guess))
+            .collect();
+        progress.start(ENDGAME_BUDGET);
+        Search { goal, weights: &position.weights, patterns, memo: HashMap::new(), work: 0, progress, settled: false }
+    }
+
+    fn weight(&self, candidates: &[usize]) -> f64 {
+        candidates.iter().map(|idx| self.weights[*idx]).sum()
+    }
+
+    // the exact outcome where it's cheap to know, otherwise one at least as good as the real one
+    fn bound(&self, candidates: &[usize], attempts: usize) -> Outcome {
+        if attempts == 0 {
+            return Outcome::FAILED;
+        }
+        if candidates.len() == 1 {
+            return Outcome::SOLVED;
+        }
+        if let Some((outcome, _)) = self.memo.get(&(candidates.to_vec(), attempts)) {
+            return *outcome;
+        }
+        // only one answer can be solved by the next guess, with one attempt left that's all of them
+        let likeliest = candidates.iter().map(|idx| self.weights[*idx]).fold(0.0, f64::max) / self.weight(candidates);
+        if attempts == 1 {
+            Outcome { expected_guesses: 1.0 + (1.0 - likeliest) * FAILURE_COST, solve_probability: likeliest }
+        } else {
+            Outcome { expected_guesses: 2.0 - likeliest, solve_probability: 1.0 }
+        }
+    }
+
+    fn split(&self, guess: usize, candidates: &[usize], attempts: usize) -> Option<Split> {
+        let mut buckets: BTreeMap<Pattern, Vec<usize>> = BTreeMap::new();
+        for candidate in candidates.iter() {
+            buckets.entry(self.patterns[guess][*candidate]).or_default().push(*candidate);
+        }
+        // a guess that can't be the answer and tells nothing apart is never worth making
+        if buckets.len() == 1 && !buckets.contains_key(&Pattern::SOLVED) {
+            return None;
+        }
+        let buckets: Vec<(Pattern, Vec<usize>)> = buckets.into_iter().collect();
+        let bounds: Vec<Outcome> = buckets.iter().map(|(_, bucket)| self.bound(bucket, attempts - 1)).collect();
+        let bound = self.combine(candidates, &buckets, &bounds);
+        Some(Split { guess, buckets, bound })
+    }
+
+    // the outcome of a guess from the outcomes of the groups it splits the candidates into
+    fn combine(&self, candidates: &[usize], buckets: &[(Pattern, Vec<usize>)], outcomes: &[Outcome]) -> Outcome {
+        let total = self.weight(candidates);
+        let mut combined = Outcome { expected_guesses: 1.0, solve_probability: 0.0 };
+        for ((pattern, bucket), outcome) in buckets.iter().zip(outcomes.iter()) {
+            let share = self.weight(bucket) / total;
+            if pattern.is_solved() {
+                combined.solve_probability += share;
+            } else {
+                combined.expected_guesses += share * outcome.expected_guesses;
+                combined.solve_probability += share * outcome.solve_probability;
+            }
+        }
+        combined
+    }
+
+    // the exact outcome of the split, or nothing as soon as it's clear it can't beat the best so far
+    fn outcome(&mut self, split: &Split, candidates: &[usize], attempts: usize, best: Option<Outcome>) -> Option<Outcome> {
+        let mut outcomes: Vec<Outcome> = split.buckets.iter().map(|(_, bucket)| self.bound(bucket, attempts - 1)).collect();
+        let mut order: Vec<usize> = (0..split.buckets.len()).collect();
+        order.sort_by_key(|idx| std::cmp::Reverse(split.buckets[*idx].1.len()));
+        for idx in order {
+            let (pattern, bucket) = &split.buckets[idx];
+            if pattern.is_solved() || bucket.len() == 1 {
+                continue;
+            }
+            outcomes[idx] = self.best(bucket.clone(), attempts - 1).0;
+            if best.is_some_and(|best| !self.goal.better(&self.combine(candidates, &split.buckets, &outcomes), &best)) {
+                return None;
+            }
+        }
+        Some(self.combine(candidates, &split.buckets, &outcomes))
+    }
+
+    // the best outcome and guess for the candidates, guess indexes below the number of candidates
+    // are the candidates themselves
+    fn best(&mut self, candidates: Vec<usize>, attempts: usize) -> (Outcome, usize) {
+        if attempts == 0 {
+            return (Outcome::FAILED, usize::MAX);
+        }
+        if candidates.len() == 1 {
+            return (Outcome::SOLVED, candidates[0]);
+        }
+        let key = (candidates, attempts);
+        if let Some(best) = self.memo.get(&key) {
+            return *best;
+        }
+        let (candidates, _) = &key;
+        let best = self.search(candidates, attempts, candidates, false);
+        self.memo.insert(key, best);
+        best
+    }
+
+    // try the most promising splits first, stopping once even the best a split could do isn't
+    // better than what has been found
+    fn try_splits(&mut self, mut splits: Vec<Split>, candidates: &[usize], attempts: usize, root: bool, best: &mut Option<(Outcome, usize)>) {
+        let goal = self.goal;
+        splits.sort_by(|a, b| goal.order(&a.bound, &b.bound));
+        for split in splits {
+            if best.is_some_and(|(best, _)| !goal.better(&split.bound, &best)) {
+                break;
+            }
+            let outcome = self.outcome(&split, candidates, attempts, best.map(|(best, _)| best));
+            // the outcome of a split whose search was cut short isn't exact
+            if self.exhausted() {
+                break;
+            }
+            if let Some(outcome) = outcome {
+                if best.map_or(true, |(best, _)| goal.better(&outcome, &best)) {
+                    *best = Some((outcome, split.guess));
+                }
+            }
+            if root {
+                self.settled = best.is_some();
+            }
+        }
+    }
+
+    // the search gives up the same way when it's cancelled as when it runs out of budget
+    fn exhausted(&self) -> bool {
+        self.work > ENDGAME_BUDGET || (self.settled && self.progress.is_cancelled())
+    }
+
+    fn search(&mut self, candidates: &[usize], attempts: usize, guessable: &[usize], root: bool) -> (Outcome, usize) {
+        self.work += candidates.len() * self.patterns.len();
+        self.progress.set(self.work);
+        if self.exhausted() {
+            return (Outcome::FAILED, usize::MAX);
+        }
+        let mut best: Option<(Outcome, usize)> = None;
+        let splits: Vec<Split> = guessable.iter().filter_map(|guess| self.split(*guess, candidates, attempts)).collect();
+        self.try_splits(splits, candidates, attempts, root, &mut best);
+
+        // a guess that can't be the answer needs at least one more guess after it, so once a
+        // candidate does as well as that there's no point trying the rest
+        if best.is_some_and(|(outcome, _)| outcome.expected_guesses <= 2.0 + EPSILON && outcome.solve_probability >= 1.0 - EPSILON) {
+            return best.unwrap();
+        }
+        let mut seen: HashSet<Vec<Pattern>> = HashSet::new();
+        let mut splits: Vec<Split> = Vec::new();
+        for guess in self.weights.len()..self.patterns.len() {
+            // guesses that split the candidates the same way are as good as each other
+            let signature: Vec<Pattern> = candidates.iter().map(|candidate| self.patterns[guess][*candidate]).collect();
+            if !seen.insert(signature) {
+                continue;
+            }
+            splits.extend(self.split(guess, candidates, attempts));
+        }
+        self.try_splits(splits, candidates, attempts, root, &mut best);
+        best.unwrap_or((Outcome::FAILED, usize::MAX))
+    }
+}
+
+impl Endgame {
+    pub fn solve(&self, position: &Position) -> Option<EndgameMove> {
+        self.solve_with(position, &Progress::default())
+    }
+
+    pub fn solve_with(&self, position: &Position, progress: &Progress) -> Option<EndgameMove> {
+        if position.candidates.is_empty() || position.attempts_left == 0 {
+            return None;
+        }
+        let candidates: HashSet<&PackedWord> = position.candidates.iter().collect();
+        let mut guesses: Vec<&PackedWord> = position.candidates.iter().collect();
+        guesses.extend(position.guesses.iter().filter(|guess| !candidates.contains(guess)));
+
+        let mut search = Search::new(self.goal, position, &guesses, progress);
+        let all: Vec<usize> = (0..position.candidates.len()).collect();
+        // unwanted candidates can be played further down the tree, where they are the answer, but
+        // aren't suggested now
+        let guessable: Vec<usize> = position.guessable_candidates().map(|(idx, _)| idx).collect();
+        let (outcome, guess) = search.search(&all, position.attempts_left, &guessable, true);
+        if search.work > ENDGAME_BUDGET {
+            return None;
+        }
+        guesses.get(guess).map(|guess| EndgameMove { guess: guess.to_string(), outcome })
+    }
+}
+
+impl Strategy for Endgame {
+    fn guess(&self, position: &Position) -> Option<PackedWord> {
+        self.solve(position).and_then(|endgame| PackedWord::new(&endgame.guess))
+    }
+}
+
+#[cfg(test)]
+mod endgame_tests {
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    fn position(words: &[&str], attempts_left: usize) -> Position {
+        Position { attempts_left, ..Position::uniform(words) }
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn single() {
+        let result = Endgame { goal: EndgameGoal::FewestGuesses }.solve(&position(&["bills"], 1)).unwrap();
+        assert_eq!(result.guess, "bills");
+        assert_eq!(result.outcome, Outcome::SOLVED);
+        assert!(Endgame { goal: EndgameGoal::FewestGuesses }.solve(&position(&["bills"], 0)).is_none());
+    }
+
+    #[test]
+    fn candidates_only() {
+        // playing the candidates in turn, 1 + 2 + 3 + 4 + 5 guesses over 5 answers
+        let result = Endgame { goal: EndgameGoal::FewestGuesses }.solve(&position(&["bills", "fills", "hills", "kills", "mills"], 6)).unwrap();
+        assert_eq!(result.guess, "bills");
+        assert!(close(result.outcome.expected_guesses, 3.0));
+        assert!(close(result.outcome.solve_probability, 1.0));
+    }
+
+    #[test]
+    fn splitting_guess() {
+        let mut result = position(&["bills", "fills", "hills", "kills", "mills"], 6);
+        result.guesses.push(word("fbhkm"));
+        let result = Endgame { goal: EndgameGoal::FewestGuesses }.solve(&result).unwrap();
+        assert_eq!(result.guess, "fbhkm");
+        assert!(close(result.outcome.expected_guesses, 2.0));
+    }
+
+    #[test]
+    fn cancelled() {
+        // the first candidate is worked out in full before the search notices it's been
+        // cancelled, so it's played even though opening with the splitting guess is better
+        let mut result = position(&["bills", "fills", "hills", "kills", "mills"], 6);
+        result.guesses.push(word("fbhkm"));
+        let progress = Progress::default();
+        progress.cancel();
+        let endgame = Endgame { goal: EndgameGoal::FewestGuesses }.solve_with(&result, &progress).unwrap();
+        assert_eq!(endgame.guess, "bills");
+        assert!(close(endgame.outcome.expected_guesses, 2.6));
+    }
+
+    #[test]
+    fn goals() {
+        // with two attempts the likeliest answer first usually solves straight away but can miss,
+        // while the splitting guess always solves but never on the first guess
+        let mut result = position(&["bills", "fills", "hills", "kills", "mills"], 2);
+        result.guesses.push(word("fbhkm"));
+        result.weights = vec![100.0, 1.0, 1.0, 1.0, 1.0];
+
+        let fewest = Endgame { goal: EndgameGoal::FewestGuesses }.solve(&result).unwrap();
+        assert_eq!(fewest.guess, "bills");
+        assert!(fewest.outcome.solve_probability < 1.0);
+
+        let most = Endgame { goal: EndgameGoal::MostSolves }.solve(&result).unwrap();
+        assert!(close(most.outcome.solve_probability, 1.0));
+        assert!(most.outcome.expected_guesses > fewest.outcome.expected_guesses);
+    }
+
+    #[test]
+    fn unwanted() {
+        let mut result = position(&["bills", "fills"], 6);
+        result.guessable[0] = false;
+        assert_eq!(Endgame { goal: EndgameGoal::FewestGuesses }.guess(&result), PackedWord::new("fills"));
+    }
+
+    #[test]
+    fn parse_goal() {
+        for goal in EndgameGoal::ALL {
+            assert_eq!(EndgameGoal::parse(goal.name()), Some(goal));
+        }
+        assert_eq!(EndgameGoal::parse("vibes"), None);
+    }
+}
@@ -0,0 +1,124 @@
+use std::default::Default;
+use super::{Blocklist, Board};
+
+pub const MAX_HISTORY: usize = 100;
+
+// everything needed to put a game back the way it was
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameState {
+    pub board: Board,
+    pub guess: String,
+    pub guess_num: usize,
+    pub blocklist: Blocklist,
+    // how many candidates the board and blocklist left, counted when the state was recorded
+    pub candidates: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl<T> History<T> {
+    // record the state from before a change, any redo states are no longer reachable
+    pub fn push(&mut self, state: T) {
+        self.undo.push(state);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let state = self.undo.pop()?;
+        self.redo.push(current);
+        Some(state)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let state = self.redo.pop()?;
+        self.undo.push(current);
+        Some(state)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // the state an undo would return to
+    pub fn last(&self) -> Option<&T> {
+        self.undo.last()
+    }
+
+    // the state a redo would return to
+    pub fn next(&self) -> Option<&T> {
+        self.redo.last()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        let result: History<usize> = Default::default();
+        assert!(!result.can_undo());
+        assert!(!result.can_redo());
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut result: History<usize> = Default::default();
+        result.push(1);
+        result.push(2);
+        assert_eq!(result.last(), Some(&2));
+        assert_eq!(result.undo(3), Some(2));
+        assert_eq!(result.next(), Some(&3));
+        assert_eq!(result.undo(2), Some(1));
+        assert_eq!(result.undo(1), None);
+        assert_eq!(result.redo(1), Some(2));
+        assert_eq!(result.redo(2), Some(3));
+        assert_eq!(result.redo(3), None);
+        assert_eq!(result.last(), Some(&2));
+    }
+
+    #[test]
+    fn push_clears_redo() {
+        let mut result: History<usize> = Default::default();
+        result.push(1);
+        assert_eq!(result.undo(2), Some(1));
+        assert!(result.can_redo());
+        result.push(1);
+        assert!(!result.can_redo());
+    }
+
+    #[test]
+    fn limit() {
+        let mut result: History<usize> = Default::default();
+        for state in 0..MAX_HISTORY + 10 {
+            result.push(state);
+        }
+        assert_eq!(result.undo.len(), MAX_HISTORY);
+        assert_eq!(result.undo[0], 10);
+    }
+}
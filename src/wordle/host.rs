@@ -0,0 +1,168 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use super::{storage, Absurdle, Board, PackedWord, Pattern, Position, Word, MAX_ATTEMPTS, MAX_LETTERS};
+
+// the app hosting a game for someone else to play, handing out the colours for their guesses
+#[derive(Debug, Clone)]
+pub struct HostGame {
+    pub board: Board,
+    pub guess_num: usize,
+    // the words the host could still say were the answer
+    position: Position,
+    // the secret word in a practice game, an evil host never has one
+    answer: Option<String>,
+}
+
+impl HostGame {
+    // a host that never picks an answer, like Absurdle every guess gets the feedback that keeps
+    // the most words in play
+    pub fn evil(position: Position) -> HostGame {
+        HostGame { board: Board::default(), guess_num: 0, position, answer: None }
+    }
+
+    // a host with a secret word picked from the candidates by the seed, the same seed always
+    // picks the same word from the same dictionary
+    pub fn practice(position: Position, seed: &str) -> Option<HostGame> {
+        if position.candidates.is_empty() {
+            return None;
+        }
+        let idx = (storage::fingerprint([seed]) % position.candidates.len() as u64) as usize;
+        let answer = position.candidates[idx].to_string();
+        Some(HostGame { board: Board::default(), guess_num: 0, position, answer: Some(answer) })
+    }
+
+    // today's date in UTC as a year, month and day, the seed for a word of the day
+    pub fn today() -> String {
+        let days = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() / 86_400) as i64;
+        Self::date(days)
+    }
+
+    // the civil date a number of days after 1970-01-01
+    fn date(days: i64) -> String {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
+    pub fn is_practice(&self) -> bool {
+        self.answer.is_some()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.position.candidates.len()
+    }
+
+    // the colours for the guess, which has to be a word that could be suggested
+    pub fn play(&mut self, guess: &str) -> Result<Pattern, String> {
+        let guess = guess.trim().to_lowercase();
+        if self.is_over() {
+            return Err("The game is over".to_string());
+        }
+        if guess.chars().count() != MAX_LETTERS {
+            return Err(format!("'{}' needs {} letters", guess, MAX_LETTERS));
+        }
+        let packed = PackedWord::new(&guess)
+            .filter(|packed| self.position.guesses.binary_search(packed).is_ok() || self.position.candidates.contains(packed))
+            .ok_or(format!("'{}' isn't in the word list", guess))?;
+        let (pattern, bucket) = match &self.answer {
+            Some(answer) => {
+                let pattern = Pattern::new(&guess, answer);
+                let bucket = self.position.partition(&packed).remove(&pattern).unwrap_or_default();
+                (pattern, bucket)
+            }
+            None => Absurdle::host(&self.position, &packed).ok_or("There are no words left to choose from")?,
+        };
+        self.position = self.position.narrow(&bucket);
+        self.board.words[self.guess_num] = Word::feedback(&guess, pattern);
+        self.guess_num += 1;
+        Ok(pattern)
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.board.words.iter().take(self.guess_num).any(|word| word.pattern().is_solved())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.guess_num >= MAX_ATTEMPTS || self.position.candidates.is_empty()
+    }
+}
+
+impl fmt::Display for HostGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_won() {
+            return write!(f, "Solved in {}", self.guess_num);
+        }
+        // out of attempts an evil host finally has to settle on one of the words it has left
+        match self.answer.clone().or(self.position.candidates.first().map(|word| word.to_string())) {
+            Some(answer) if self.is_over() => write!(f, "Out of attempts, the answer was {}", answer),
+            _ => write!(f, "{} words left, {} attempts left", self.remaining(), MAX_ATTEMPTS - self.guess_num),
+        }
+    }
+}
+
+#[cfg(test)]
+mod host_tests {
+    use super::*;
+
+    #[test]
+    fn evil() {
+        let mut result = HostGame::evil(Position::uniform(&["bills", "fills", "hills", "kills", "mills"]));
+        assert_eq!(result.play("Bills").unwrap().to_string(), ".gggg");
+        assert_eq!(result.board.words[0].value(), "bills");
+        assert_eq!(result.to_string(), "4 words left, 5 attempts left");
+
+        assert!(result.play("bill").is_err());
+        assert!(result.play("pills").is_err());
+        assert_eq!(result.guess_num, 1);
+
+        for word in ["fills", "hills", "kills"] {
+            result.play(word).unwrap();
+        }
+        assert_eq!(result.remaining(), 1);
+        assert!(result.play("mills").unwrap().is_solved());
+        assert_eq!(result.to_string(), "Solved in 5");
+        assert!(result.play("mills").is_err());
+    }
+
+    #[test]
+    fn out_of_attempts() {
+        let mut result = HostGame::evil(Position::uniform(&["bills", "fills", "hills", "kills", "mills", "pills", "tills"]));
+        for word in ["bills", "fills", "hills", "kills", "mills", "pills"] {
+            result.play(word).unwrap();
+        }
+        assert!(result.is_over());
+        assert!(!result.is_won());
+        assert_eq!(result.to_string(), "Out of attempts, the answer was tills");
+    }
+
+    #[test]
+    fn practice() {
+        let words = ["bills", "fills", "hills", "kills", "mills"];
+        let mut result = HostGame::practice(Position::uniform(&words), "2024-01-01").unwrap();
+        assert!(result.is_practice());
+        let answer = result.answer.clone().unwrap();
+        assert_eq!(HostGame::practice(Position::uniform(&words), "2024-01-01").unwrap().answer, Some(answer.clone()));
+
+        let guess = words.iter().find(|word| **word != answer).unwrap();
+        assert_eq!(result.play(guess).unwrap(), Pattern::new(guess, &answer));
+        assert_eq!(result.remaining(), 4);
+        assert!(result.play(&answer).unwrap().is_solved());
+        assert_eq!(result.to_string(), "Solved in 2");
+        assert!(HostGame::practice(Position::default(), "seed").is_none());
+    }
+
+    #[test]
+    fn date() {
+        assert_eq!(HostGame::date(0), "1970-01-01");
+        assert_eq!(HostGame::date(19_722), "2023-12-31");
+        assert_eq!(HostGame::date(11_016), "2000-02-29");
+        assert_eq!(HostGame::today().len(), 10);
+    }
+}
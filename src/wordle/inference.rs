@@ -0,0 +1,90 @@
+use super::{Position, Progress, ShareGrid};
+
+// the feedback patterns there are, one per combination of colours
+const PATTERNS: usize = 243;
+
+// the answers that could have given everyone's share grids, without knowing anyone's guesses
+pub struct Inference;
+
+impl Inference {
+    // every candidate that each row of every grid could have come from, with how likely it is to
+    // be the answer, most likely first, the likelihoods add up to one
+    pub fn rank_with(position: &Position, grids: &[ShareGrid], progress: &Progress) -> Vec<(f64, String)> {
+        // how many guesses give each pattern against each candidate
+        let mut counts = vec![[0u32; PATTERNS]; position.candidates.len()];
+        progress.start(position.guesses.len());
+        for guess in position.guesses.iter() {
+            if progress.is_cancelled() {
+                return Vec::new();
+            }
+            for (count, pattern) in counts.iter_mut().zip(position.patterns(guess)) {
+                count[pattern.0 as usize] += 1;
+            }
+            progress.step();
+        }
+
+        // a row is as likely as the share of guesses that give it, a solved row only comes from
+        // the answer itself, and everything after a solved row is ignored
+        let guesses = position.guesses.len().max(1) as f64;
+        let mut ranked: Vec<(f64, String)> = Vec::new();
+        for (idx, candidate) in position.candidates.iter().enumerate() {
+            let mut likelihood = position.weights[idx].ln();
+            for grid in grids.iter() {
+                for row in grid.rows.iter() {
+                    if row.pattern.is_solved() {
+                        break;
+                    }
+                    likelihood += (counts[idx][row.pattern.0 as usize] as f64 / guesses).ln();
+                }
+            }
+            if likelihood.is_finite() {
+                ranked.push((likelihood, candidate.to_string()));
+            }
+        }
+
+        let most = ranked.iter().map(|(likelihood, _)| *likelihood).fold(f64::NEG_INFINITY, f64::max);
+        let total: f64 = ranked.iter().map(|(likelihood, _)| (likelihood - most).exp()).sum();
+        for (likelihood, _) in ranked.iter_mut() {
+            *likelihood = (*likelihood - most).exp() / total;
+        }
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod inference_tests {
+    use crate::wordle::{Pattern, ShareRow};
+    use super::*;
+
+    fn grid(patterns: &[&str]) -> ShareGrid {
+        let rows = patterns.iter().map(|pattern| {
+            let (guess, answer) = pattern.split_once('>').unwrap();
+            ShareRow { pattern: Pattern::new(guess, answer), guess: None }
+        }).collect();
+        ShareGrid { number: None, hard: false, rows }
+    }
+
+    #[test]
+    fn rank() {
+        let position = Position::uniform(&["crane", "slate", "plate", "fuzzy"]);
+        let grids = [grid(&["crane>slate", "plate>slate", "slate>slate"]), grid(&["fuzzy>slate", "slate>slate"])];
+        let result = Inference::rank_with(&position, &grids, &Progress::default());
+        // no guess gives crane .gggg or fuzzy ..g.g, and the grids can't tell plate and slate apart
+        assert_eq!(result, vec![(0.5, String::from("plate")), (0.5, String::from("slate"))]);
+
+        // three guesses are all grey against fuzzy but only one is against each other word
+        let result = Inference::rank_with(&position, &[grid(&["fuzzy>crane"])], &Progress::default());
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[0].1, "fuzzy");
+        let total: f64 = result.iter().map(|(likelihood, _)| likelihood).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cancelled() {
+        let progress = Progress::default();
+        progress.cancel();
+        assert!(Inference::rank_with(&Position::uniform(&["crane", "slate"]), &[grid(&["crane>slate"])], &progress).is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use super::LetterState;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Letter {
     // a char would be more efficient here for storage, but would
     // require conversion to string at runtime
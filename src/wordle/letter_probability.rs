@@ -1,63 +1,80 @@
 use std::default::Default;
-use crate::wordle::{MAX_LETTERS};
+use crate::wordle::{PackedWord, ALPHABET, MAX_LETTERS};
 
-#[derive(Debug, Clone)]
+// how often each letter is in each position, words are lowercase a to z
+#[derive(Debug, Clone, PartialEq)]
 pub struct LetterProbability {
-    counts: Vec<std::collections::HashMap<char, u64>>,
+    counts: [[u64; ALPHABET]; MAX_LETTERS],
     word_count: u32
 }
 
 impl Default for LetterProbability {
     fn default() -> Self {
         Self {
-            counts:  vec![Default::default(); crate::wordle::word::MAX_LETTERS],
+            counts: [[0; ALPHABET]; MAX_LETTERS],
             word_count: 0u32
         }
     }
 }
 
 impl LetterProbability {
-    pub fn add_word(self: &mut Self, word: &String){
+    #[allow(clippy::needless_arbitrary_self_type)]
+    pub fn add_word(self: &mut Self, word: &str){
         if word.len() > self.counts.len() {
             panic!("Word Length must not exceed set length of {} characters", MAX_LETTERS);
         }
-        for (index, letter) in word.chars().enumerate() {
-            self.add_letter(letter, index);
+        self.add(&Self::pack(word));
+    }
+
+    pub fn add(&mut self, word: &PackedWord) {
+        for (index, letter) in word.letters().iter().enumerate() {
+            self.counts[index][*letter as usize] += 1;
         }
         self.word_count += 1;
     }
 
-    fn add_letter(self: &mut Self, letter: char, index: usize) {
-        let map = self.counts.iter_mut().nth(index).unwrap();
-        if let Some(count) = map.get_mut(&letter) {
-            *count = *count + 1;
-        }
-        else {
-            map.insert(letter.clone(), 1);
+    // take back a word that was added
+    pub fn remove_word(&mut self, word: &str) {
+        let word = Self::pack(word);
+        for (index, letter) in word.letters().iter().enumerate() {
+            let count = &mut self.counts[index][*letter as usize];
+            *count = count.saturating_sub(1);
         }
+        self.word_count = self.word_count.saturating_sub(1);
     }
 
-    pub fn score_word(self: &Self, word: &String) -> f64 {
+    #[allow(clippy::needless_arbitrary_self_type)]
+    pub fn score_word(self: &Self, word: &PackedWord) -> f64 {
         let mut ret = 0.0f64;
-        for (index, letter) in word.chars().enumerate() {
-            let map = self.counts.iter().nth(index).unwrap();
-            let value = map.get(&letter);
-            match value {
-                Some(value) => {
-                    let num : f64 = *value as f64;
-                    ret += num / self.word_count as f64;
+        for (index, letter) in word.letters().iter().enumerate() {
+            match self.counts[index][*letter as usize] {
+                0 => { return 0.0f64; }
+                value => {
+                    ret += value as f64 / self.word_count as f64;
                 }
-                None => { return 0.0f64; }
             }
         }
         ret
     }
+
+    // anything else would be counted as no letter at all, so it's a mistake to add it
+    fn pack(word: &str) -> PackedWord {
+        match PackedWord::new(word) {
+            Some(word) => word,
+            None => panic!("Word {} has letters other than a to z", word),
+        }
+    }
 }
 
 #[cfg(test)]
 mod letter_probability_tests {
+    use crate::wordle::test_word as packed;
     use super::*;
 
+    fn letter(c: char) -> usize {
+        (c as u8 - b'a') as usize
+    }
+
     fn float_compare(a: f64, b: f64, digits: usize) -> bool {
         let a_int = (a * 10f64.powi(digits as i32)).round() as i64;
         let b_int = (b * 10f64.powi(digits as i32)).round() as i64;
@@ -75,20 +92,20 @@ mod letter_probability_tests {
         let mut result: LetterProbability = Default::default();
         result.add_word(&String::from("hello"));
         assert_eq!(result.word_count, 1);
-        assert_eq!(result.counts[0].get(&'h').unwrap(), &1u64);
-        assert_eq!(result.counts[0].get(&'e'), None);
+        assert_eq!(result.counts[0][letter('h')], 1u64);
+        assert_eq!(result.counts[0][letter('e')], 0);
 
-        assert_eq!(result.counts[1].get(&'e').unwrap(), &1u64);
-        assert_eq!(result.counts[1].get(&'k'), None);
+        assert_eq!(result.counts[1][letter('e')], 1u64);
+        assert_eq!(result.counts[1][letter('k')], 0);
 
-        assert_eq!(result.counts[2].get(&'l').unwrap(), &1u64);
-        assert_eq!(result.counts[2].get(&'z'), None);
+        assert_eq!(result.counts[2][letter('l')], 1u64);
+        assert_eq!(result.counts[2][letter('z')], 0);
 
-        assert_eq!(result.counts[3].get(&'l').unwrap(), &1u64);
-        assert_eq!(result.counts[3].get(&'z'), None);
+        assert_eq!(result.counts[3][letter('l')], 1u64);
+        assert_eq!(result.counts[3][letter('z')], 0);
 
-        assert_eq!(result.counts[4].get(&'o').unwrap(), &1u64);
-        assert_eq!(result.counts[4].get(&'z'), None);
+        assert_eq!(result.counts[4][letter('o')], 1u64);
+        assert_eq!(result.counts[4][letter('z')], 0);
     }
 
     #[test]
@@ -96,14 +113,32 @@ mod letter_probability_tests {
         let mut result: LetterProbability = Default::default();
         result.add_word(&String::from("hello"));
         result.add_word(&String::from("world"));
-        assert!(float_compare(result.score_word(&String::from("hello")), 3.0f64, 3));
-        assert_eq!(result.score_word(&String::from("world")), 3.0f64);
-        assert_eq!(result.score_word(&String::from("horld")), 3.0f64);
+        assert!(float_compare(result.score_word(&packed("hello")), 3.0f64, 3));
+        assert_eq!(result.score_word(&packed("world")), 3.0f64);
+        assert_eq!(result.score_word(&packed("horld")), 3.0f64);
         result.add_word(&String::from("weird"));
-        assert!(float_compare(result.score_word(&String::from("hello")), 2.333f64, 3));
-        assert!(float_compare(result.score_word(&String::from("world")), 2.6666f64, 3));
-        assert!(float_compare(result.score_word(&String::from("horld")) , 2.3333f64, 3));
+        assert!(float_compare(result.score_word(&packed("hello")), 2.333f64, 3));
+        assert!(float_compare(result.score_word(&packed("world")), 2.6666f64, 3));
+        assert!(float_compare(result.score_word(&packed("horld")) , 2.3333f64, 3));
 
-        assert_eq!(result.score_word(&String::from("rends")), 0.0f64);
+        assert_eq!(result.score_word(&packed("rends")), 0.0f64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_word_panic() {
+        let mut result: LetterProbability = Default::default();
+        result.add_word("HELLO");
+    }
+
+    #[test]
+    fn remove_word() {
+        let mut result: LetterProbability = Default::default();
+        result.add_word("hello");
+        result.add_word("world");
+        result.remove_word("hello");
+        assert_eq!(result.word_count, 1);
+        assert_eq!(result.counts[0][letter('h')], 0);
+        assert_eq!(result.score_word(&packed("world")), 5.0f64);
     }
 }
@@ -7,6 +7,7 @@
         Present,
     }
 
+    #[allow(clippy::derivable_impls)]
     impl Default for LetterState {
         fn default() -> Self {
             LetterState::Disabled
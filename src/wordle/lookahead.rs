@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+use super::{MinExpectedGuesses, PackedWord, Position, Progress, Strategy};
+
+// how many of the best guesses by a single step are looked at two steps ahead
+pub const LOOKAHEAD_BREADTH: usize = 10;
+pub const LOOKAHEAD_TIME_LIMIT: Duration = Duration::from_secs(2);
+
+// the guess whose feedback leaves the easiest follow-up guesses, rather than just the smallest groups
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lookahead {
+    pub breadth: usize,
+    // once this has passed the best guess scored so far is used
+    pub time_limit: Duration,
+}
+
+impl Default for Lookahead {
+    fn default() -> Self {
+        Self {
+            breadth: LOOKAHEAD_BREADTH,
+            time_limit: LOOKAHEAD_TIME_LIMIT,
+        }
+    }
+}
+
+impl Lookahead {
+    // the guesses with the fewest expected guesses looking one step ahead, best first
+    pub fn shortlist(&self, position: &Position) -> Vec<PackedWord> {
+        let mut scored: Vec<(f64, PackedWord)> = position.guess_pool(position.pool_limit()).into_iter()
+            .map(|word| (MinExpectedGuesses::expected_guesses(position, &word), word))
+            .collect();
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        scored.into_iter().take(self.breadth.max(1)).map(|(_, word)| word).collect()
+    }
+
+    // the expected number of guesses to finish, counting this one, when every feedback is followed
+    // by the best of the bucket's own candidates and the other shortlisted guesses
+    pub fn expected_guesses(position: &Position, guess: &PackedWord, follow_ups: &[PackedWord]) -> f64 {
+        let total = position.total_weight();
+        let mut expected = 1.0;
+        for (pattern, bucket) in position.partition(guess) {
+            if pattern.is_solved() {
+                continue;
+            }
+            let weight: f64 = bucket.iter().map(|idx| position.weights[*idx]).sum();
+            let rest = Position {
+                candidates: bucket.iter().map(|idx| position.candidates[*idx]).collect(),
+                weights: bucket.iter().map(|idx| position.weights[*idx]).collect(),
+                guessable: bucket.iter().map(|idx| position.guessable[*idx]).collect(),
+                guesses: Vec::new(),
+                attempts_left: position.attempts_left.saturating_sub(1),
+                lookup: position.lookup.as_ref().map(|lookup| lookup.narrow(&bucket)),
+                letters: None,
+            };
+            let follow_up = rest.guessable_candidates().map(|(_, word)| word)
+                .chain(follow_ups.iter())
+                .map(|word| MinExpectedGuesses::expected_guesses(&rest, word))
+                .fold(f64::INFINITY, f64::min);
+            // nothing left that may be guessed, so the best that can be hoped for is one each
+            let follow_up = if follow_up.is_finite() { follow_up } else { MinExpectedGuesses::remaining_guesses(bucket.len()) };
+            expected += weight / total * follow_up;
+        }
+        expected
+    }
+}
+
+impl Strategy for Lookahead {
+    fn guess(&self, position: &Position) -> Option<PackedWord> {
+        self.guess_with(position, &Progress::default())
+    }
+
+    fn guess_with(&self, position: &Position, progress: &Progress) -> Option<PackedWord> {
+        if position.candidates.len() <= 2 {
+            return MinExpectedGuesses.guess(position);
+        }
+        let start = Instant::now();
+        let shortlist = self.shortlist(position);
+        progress.start(shortlist.len());
+        let mut guessed_word: Option<(f64, &PackedWord)> = None;
+        for word in shortlist.iter() {
+            if guessed_word.is_some() && (start.elapsed() > self.time_limit || progress.is_cancelled()) {
+                break;
+            }
+            let expected = Self::expected_guesses(position, word, &shortlist);
+            if guessed_word.map_or(true, |(best, _)| expected < best) {
+                guessed_word = Some((expected, word));
+            }
+            progress.step();
+        }
+        guessed_word.map(|(_, word)| *word)
+    }
+
+    // only the shortlist is looked at two steps ahead, the rest keep their single step score
+    fn scores(&self, position: &Position, guesses: &[PackedWord]) -> Vec<f64> {
+        let shortlist = self.shortlist(position);
+        guesses.iter().map(|word| if shortlist.contains(word) {
+            Self::expected_guesses(position, word, &shortlist)
+        } else {
+            MinExpectedGuesses::expected_guesses(position, word)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod lookahead_tests {
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    #[test]
+    fn shortlist() {
+        let mut position = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        position.guesses.push(word("fbhkm"));
+        let lookahead = Lookahead { breadth: 2, ..Default::default() };
+        let result = lookahead.shortlist(&position);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], word("fbhkm"));
+    }
+
+    #[test]
+    fn expected_guesses() {
+        let position = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        // the four left after a wrong candidate can only be followed by one of them
+        let result = Lookahead::expected_guesses(&position, &word("bills"), &[]);
+        let follow_up = 1.0 + 0.75 * MinExpectedGuesses::remaining_guesses(3);
+        assert!((result - (1.0 + 0.8 * follow_up)).abs() < 1e-9);
+
+        // a follow-up that tells the four apart finishes in two more
+        let result = Lookahead::expected_guesses(&position, &word("bills"), &[word("fhkma")]);
+        assert!((result - (1.0 + 0.8 * 2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn guess() {
+        let mut position = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        position.guesses.push(word("fbhkm"));
+        assert_eq!(Lookahead::default().guess(&position), PackedWord::new("fbhkm"));
+        assert_eq!(Lookahead::default().guess(&Position::default()), None);
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+use std::fmt;
+use super::{PackedWord, Position, Progress, Strategy};
+
+// how many of the best guesses are tried at each step of the guarantee search
+pub const GUARANTEE_BRANCHES: usize = 10;
+// how many guesses the guarantee search may try in total before giving up
+pub const GUARANTEE_BUDGET: usize = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guarantee {
+    // solvable in at most this many more guesses whatever the answer is
+    Guaranteed(usize),
+    // some answers can't be told apart in the attempts left
+    Impossible,
+    // the search gave up before finding a way to guarantee a solve
+    Unknown,
+}
+
+impl fmt::Display for Guarantee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Guarantee::Guaranteed(1) => write!(f, "Guaranteed solve next guess"),
+            Guarantee::Guaranteed(guesses) => write!(f, "Guaranteed solve within {} guesses", guesses),
+            Guarantee::Impossible => write!(f, "A solve can't be guaranteed"),
+            Guarantee::Unknown => write!(f, "No guaranteed solve found"),
+        }
+    }
+}
+
+// the guess that leaves the fewest candidates in the worst case
+pub struct Minimax;
+
+impl Minimax {
+    // the size of the largest group of candidates that would get the same feedback
+    pub fn worst_case(position: &Position, guess: &PackedWord) -> usize {
+        position.partition(guess).values().map(|bucket| bucket.len()).max().unwrap_or(0)
+    }
+
+    // the guesses from best to worst, ties go to a guess that could be the answer, then alphabetical
+    pub fn ranked(position: &Position, limit: usize) -> Vec<(usize, PackedWord)> {
+        let candidates: HashSet<&PackedWord> = position.candidates.iter().collect();
+        let mut ranked: Vec<(usize, bool, PackedWord)> = position.guess_pool(limit).into_iter()
+            .map(|word| (Self::worst_case(position, &word), !candidates.contains(&word), word))
+            .collect();
+        ranked.sort();
+        ranked.into_iter().map(|(worst, _, word)| (worst, word)).collect()
+    }
+
+    // the most candidates that any number of guesses can always tell apart, every guess can
+    // solve one answer and split the rest into at most 242 groups
+    fn separable(attempts: usize) -> usize {
+        let mut separable: usize = 0;
+        for _ in 0..attempts {
+            separable = separable.saturating_mul(242).saturating_add(1);
+        }
+        separable
+    }
+
+    pub fn guarantee(position: &Position) -> Guarantee {
+        let mut budget = GUARANTEE_BUDGET;
+        match Self::search(position, &mut budget) {
+            Some(guesses) => Guarantee::Guaranteed(guesses),
+            None if Self::impossible(position) => Guarantee::Impossible,
+            None => Guarantee::Unknown,
+        }
+    }
+
+    fn impossible(position: &Position) -> bool {
+        position.candidates.len() > Self::separable(position.attempts_left)
+    }
+
+    // the most guesses needed to solve every candidate, if a way of doing it was found
+    fn search(position: &Position, budget: &mut usize) -> Option<usize> {
+        let candidates = position.candidates.len();
+        if candidates == 0 {
+            return Some(0);
+        }
+        if Self::impossible(position) {
+            return None;
+        }
+        if candidates <= 2 {
+            return Some(candidates);
+        }
+
+        // with enough attempts every candidate can be played in turn, but a good guess may do better
+        let mut best = if candidates <= position.attempts_left { Some(candidates) } else { None };
+        for (_, guess) in Self::ranked(position, position.pool_limit()).into_iter().take(GUARANTEE_BRANCHES) {
+            if *budget == 0 {
+                break;
+            }
+            *budget -= 1;
+
+            let mut worst = Some(1);
+            for (pattern, bucket) in position.partition(&guess) {
+                if pattern.is_solved() {
+                    continue;
+                }
+                match Self::search(&position.narrow(&bucket), budget) {
+                    Some(guesses) => worst = worst.map(|worst| worst.max(guesses + 1)),
+                    None => {
+                        worst = None;
+                        break;
+                    }
+                }
+            }
+            if let Some(worst) = worst {
+                if best.map_or(true, |best| worst < best) {
+                    best = Some(worst);
+                }
+                // nothing can do better than telling every candidate apart with this guess
+                if worst <= 2 {
+                    break;
+                }
+            }
+        }
+        best
+    }
+}
+
+impl Strategy for Minimax {
+    fn guess(&self, position: &Position) -> Option<PackedWord> {
+        self.guess_with(position, &Progress::default())
+    }
+
+    // the first of the ranked guesses, or the best of those looked at before being cancelled
+    fn guess_with(&self, position: &Position, progress: &Progress) -> Option<PackedWord> {
+        if position.candidates.is_empty() {
+            return None;
+        }
+        let candidates: HashSet<&PackedWord> = position.candidates.iter().collect();
+        let pool = position.guess_pool(position.pool_limit());
+        progress.start(pool.len());
+        let mut guessed_word: Option<(usize, bool, PackedWord)> = None;
+        for word in pool {
+            if guessed_word.is_some() && progress.is_cancelled() {
+                break;
+            }
+            let ranking = (Self::worst_case(position, &word), !candidates.contains(&word), word);
+            if guessed_word.as_ref().map_or(true, |best| ranking < *best) {
+                guessed_word = Some(ranking);
+            }
+            progress.step();
+        }
+        guessed_word.map(|(_, _, word)| word)
+    }
+
+    fn scores(&self, position: &Position, guesses: &[PackedWord]) -> Vec<f64> {
+        guesses.iter().map(|word| Self::worst_case(position, word) as f64).collect()
+    }
+}
+
+#[cfg(test)]
+mod minimax_tests {
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    fn position(words: &[&str], attempts_left: usize) -> Position {
+        Position { attempts_left, ..Position::uniform(words) }
+    }
+
+    #[test]
+    fn worst_case() {
+        let position = position(&["bills", "fills", "hills", "kills", "mills"], 6);
+        assert_eq!(Minimax::worst_case(&position, &word("bills")), 4);
+        assert_eq!(Minimax::worst_case(&position, &word("fbhkm")), 1);
+        assert_eq!(Minimax.scores(&position, &[word("bills"), word("fbhkm")]), vec![4.0, 1.0]);
+    }
+
+    #[test]
+    fn guess() {
+        let mut position = position(&["bills", "fills", "hills", "kills", "mills"], 6);
+        // every candidate is as bad as the others, so the first alphabetically
+        assert_eq!(Minimax.guess(&position), PackedWord::new("bills"));
+
+        position.guesses.push(word("fbhkm"));
+        assert_eq!(Minimax.guess(&position), PackedWord::new("fbhkm"));
+
+        // a candidate that splits as well as a non candidate wins the tie
+        position.guesses.push(word("abfhk"));
+        position.candidates = vec![word("bills"), word("fills")];
+        assert_eq!(Minimax.guess(&position), PackedWord::new("bills"));
+
+        assert_eq!(Minimax.guess(&Position::default()), None);
+    }
+
+    #[test]
+    fn guarantee() {
+        let words = ["bills", "fills", "hills", "kills", "mills"];
+        assert_eq!(Minimax::guarantee(&position(&words, 6)), Guarantee::Guaranteed(5));
+        assert_eq!(Minimax::guarantee(&position(&words, 4)), Guarantee::Unknown);
+        assert_eq!(Minimax::guarantee(&position(&words, 1)), Guarantee::Impossible);
+
+        let mut result = position(&words, 2);
+        result.guesses.push(word("fbhkm"));
+        assert_eq!(Minimax::guarantee(&result), Guarantee::Guaranteed(2));
+        assert_eq!(Minimax::guarantee(&position(&["bills"], 1)), Guarantee::Guaranteed(1));
+    }
+
+    #[test]
+    fn separable() {
+        assert_eq!(Minimax::separable(0), 0);
+        assert_eq!(Minimax::separable(1), 1);
+        assert_eq!(Minimax::separable(2), 243);
+    }
+}
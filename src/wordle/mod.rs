@@ -4,9 +4,66 @@ mod word;
 mod board;
 mod statistics;
 mod letter_probability;
+mod conflict;
+mod history;
+mod storage;
+mod blocklist;
+mod past_answers;
+mod dictionary;
+mod packed_word;
+mod pattern;
+mod priors;
+mod strategy;
+mod minimax;
+mod endgame;
+mod lookahead;
+mod simulation;
+mod decision_tree;
+mod openers;
+mod pattern_matrix;
+mod narrowing;
+mod progress;
+mod multi_board;
+mod absurdle;
+mod host;
+mod commentary;
+mod analysis;
+mod share;
+mod inference;
+mod reverse;
+mod notation;
+mod session;
 
 pub use letter_state::*;
 pub use letter::*;
 pub use word::*;
 pub use board::*;
-pub use statistics::*;
\ No newline at end of file
+pub use statistics::*;
+pub use conflict::*;
+pub use history::*;
+pub use blocklist::*;
+pub use past_answers::*;
+pub use dictionary::*;
+pub use packed_word::*;
+pub use pattern::*;
+pub use priors::*;
+pub use strategy::*;
+pub use minimax::*;
+pub use endgame::*;
+pub use lookahead::*;
+pub use simulation::*;
+pub use decision_tree::*;
+pub use openers::*;
+pub use pattern_matrix::*;
+pub use narrowing::*;
+pub use progress::*;
+pub use multi_board::*;
+pub use absurdle::*;
+pub use host::*;
+pub use commentary::*;
+pub use analysis::*;
+pub use share::*;
+pub use inference::*;
+pub use reverse::*;
+pub use notation::*;
+pub use session::*;
@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+use std::fmt;
+use super::{Board, LetterState, MinExpectedGuesses, PackedWord, Position, Progress, GUESS_BUDGET, MAX_ATTEMPTS};
+
+// how many words are found at once in Wordle, Dordle, Quordle and Octordle
+pub const BOARD_COUNTS: [usize; 4] = [1, 2, 4, 8];
+
+// several hidden words played at once, every guess goes on each board that isn't solved yet and
+// there is an extra attempt for every board after the first
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiBoard {
+    pub boards: Vec<Board>,
+    pub guess_num: usize,
+}
+
+impl MultiBoard {
+    pub fn new(count: usize) -> MultiBoard {
+        MultiBoard {
+            boards: vec![Board::with_rows(Self::attempts_for(count)); count.max(1)],
+            guess_num: 0,
+        }
+    }
+
+    // the game the number of boards is played in
+    pub fn name(count: usize) -> &'static str {
+        match count {
+            1 => "Wordle",
+            2 => "Dordle",
+            4 => "Quordle",
+            8 => "Octordle",
+            _ => "Multi-board",
+        }
+    }
+
+    pub fn attempts_for(count: usize) -> usize {
+        count.max(1) + MAX_ATTEMPTS - 1
+    }
+
+    pub fn attempts_left(&self) -> usize {
+        Self::attempts_for(self.boards.len()).saturating_sub(self.guess_num)
+    }
+
+    // a board is solved once a row is all green
+    pub fn is_solved(board: &Board) -> bool {
+        board.words.iter().any(|word| word.letters.iter().all(|letter| letter.get_state() == LetterState::Correct))
+    }
+
+    pub fn unsolved(&self) -> impl Iterator<Item = &Board> {
+        self.boards.iter().filter(|board| !Self::is_solved(board))
+    }
+
+    // enter the guess on every board still being played
+    pub fn play(&mut self, guess: &str) {
+        if self.attempts_left() == 0 {
+            return;
+        }
+        for board in self.boards.iter_mut().filter(|board| !Self::is_solved(board)) {
+            board.set_word(self.guess_num, guess);
+        }
+        self.guess_num += 1;
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.unsolved().next().is_none() || self.attempts_left() == 0
+    }
+}
+
+impl fmt::Display for MultiBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let solved = self.boards.iter().filter(|board| Self::is_solved(board)).count();
+        write!(f, "{} of {} solved, {} attempts left", solved, self.boards.len(), self.attempts_left())
+    }
+}
+
+// the guess that does the most across every board still being played
+pub struct Combined;
+
+impl Combined {
+    // a board down to one candidate is solved by playing it, otherwise the guess with the fewest
+    // expected guesses added up over the boards
+    pub fn guess_with(positions: &[Position], progress: &Progress) -> Option<PackedWord> {
+        let positions: Vec<&Position> = positions.iter().filter(|position| !position.candidates.is_empty()).collect();
+        if let Some(position) = positions.iter().find(|position| position.candidates.len() == 1 && position.guessable[0]) {
+            return Some(position.candidates[0]);
+        }
+        let total: usize = positions.iter().map(|position| position.candidates.len()).sum();
+        let limit = (GUESS_BUDGET / total.max(1) / positions.len().max(1)).max(1);
+        let mut seen: HashSet<PackedWord> = HashSet::new();
+        let pool: Vec<PackedWord> = positions.iter()
+            .flat_map(|position| position.guess_pool(limit))
+            .filter(|word| seen.insert(*word))
+            .collect();
+
+        progress.start(pool.len());
+        let mut guessed_word: Option<(f64, PackedWord)> = None;
+        for word in pool {
+            if guessed_word.is_some() && progress.is_cancelled() {
+                break;
+            }
+            let expected: f64 = positions.iter().map(|position| MinExpectedGuesses::expected_guesses(position, &word)).sum();
+            if guessed_word.as_ref().map_or(true, |(best, _)| expected < *best) {
+                guessed_word = Some((expected, word));
+            }
+            progress.step();
+        }
+        guessed_word.map(|(_, word)| word)
+    }
+}
+
+#[cfg(test)]
+mod multi_board_tests {
+    use crate::wordle::{Pattern, Word};
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    #[test]
+    fn new() {
+        let result = MultiBoard::new(4);
+        assert_eq!(result.boards.len(), 4);
+        assert_eq!(result.boards[0].words.len(), 9);
+        assert_eq!(result.attempts_left(), 9);
+        assert_eq!(MultiBoard::attempts_for(8), 13);
+        assert!(!result.is_over());
+    }
+
+    #[test]
+    fn play() {
+        let mut result = MultiBoard::new(2);
+        result.play("crane");
+        assert_eq!(result.boards[1].words[0].value(), "crane");
+        result.boards[0].words[0] = Word::feedback("crane", Pattern::SOLVED);
+        assert_eq!(result.unsolved().count(), 1);
+
+        // a solved board gets no more guesses
+        result.play("slate");
+        assert_eq!(result.boards[0].words[1].value(), "     ");
+        assert_eq!(result.boards[1].words[1].value(), "slate");
+        assert_eq!(result.to_string(), "1 of 2 solved, 5 attempts left");
+    }
+
+    #[test]
+    fn combined() {
+        let first = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        let mut second = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        second.guesses.push(word("fbhkm"));
+        assert_eq!(Combined::guess_with(&[first.clone(), second], &Progress::default()), PackedWord::new("fbhkm"));
+
+        // a board with one candidate left is solved first
+        let solved = Position::uniform(&["crane"]);
+        assert_eq!(Combined::guess_with(&[first, solved], &Progress::default()), PackedWord::new("crane"));
+        assert_eq!(Combined::guess_with(&[], &Progress::default()), None);
+    }
+}
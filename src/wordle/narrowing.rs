@@ -0,0 +1,155 @@
+use crate::wordle::letter_probability::LetterProbability;
+use super::Word;
+
+// the candidates left after each row of feedback, so a new row only filters what the rows before
+// it left and taking rows away goes straight back to an earlier set
+#[derive(Debug, Clone, Default)]
+pub struct Narrowing {
+    // the first level is every candidate before any feedback
+    levels: Vec<Level>,
+}
+
+#[derive(Debug, Clone)]
+struct Level {
+    filter: Option<Word>,
+    candidates: Vec<String>,
+    letters: LetterProbability,
+}
+
+impl Narrowing {
+    pub fn new(mut candidates: Vec<String>) -> Narrowing {
+        candidates.sort();
+        let mut letters = LetterProbability::default();
+        for word in candidates.iter() {
+            letters.add_word(word);
+        }
+        Narrowing { levels: vec![Level { filter: None, candidates, letters }] }
+    }
+
+    // nothing to narrow from, until the candidates before any feedback are known again
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    // keep the levels for the rows the filters still start with and narrow from there
+    pub fn apply(&mut self, filters: &[Word]) {
+        if self.levels.is_empty() {
+            return;
+        }
+        let kept = self.levels[1..].iter().zip(filters.iter())
+            .take_while(|(level, filter)| level.filter.as_ref() == Some(*filter))
+            .count();
+        self.levels.truncate(kept + 1);
+        for filter in filters[kept..].iter() {
+            let parent = self.levels.last().unwrap();
+            let (candidates, removed): (Vec<String>, Vec<String>) = parent.candidates.iter().cloned()
+                .partition(|word| !filter.filter(word));
+            // take away whichever is fewer words, the ones removed or the ones left
+            let letters = if removed.len() < candidates.len() {
+                let mut letters = parent.letters.clone();
+                for word in removed.iter() {
+                    letters.remove_word(word);
+                }
+                letters
+            } else {
+                let mut letters = LetterProbability::default();
+                for word in candidates.iter() {
+                    letters.add_word(word);
+                }
+                letters
+            };
+            self.levels.push(Level { filter: Some(filter.clone()), candidates, letters });
+        }
+    }
+
+    // the level for exactly these rows of feedback, if it has been worked out
+    fn level(&self, filters: &[Word]) -> Option<&Level> {
+        let level = self.levels.get(filters.len())?;
+        let matches = self.levels[1..=filters.len()].iter().zip(filters.iter())
+            .all(|(level, filter)| level.filter.as_ref() == Some(filter));
+        if matches { Some(level) } else { None }
+    }
+
+    // the candidates left after the rows of feedback, in alphabetical order
+    pub fn candidates(&self, filters: &[Word]) -> Option<&[String]> {
+        self.level(filters).map(|level| level.candidates.as_slice())
+    }
+
+    pub fn letters(&self, filters: &[Word]) -> Option<&LetterProbability> {
+        self.level(filters).map(|level| &level.letters)
+    }
+
+    // a word that can no longer be the answer, from every level
+    pub fn remove(&mut self, word: &str) {
+        for level in self.levels.iter_mut() {
+            if let Ok(idx) = level.candidates.binary_search_by(|candidate| candidate.as_str().cmp(word)) {
+                level.candidates.remove(idx);
+                level.letters.remove_word(word);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.levels.clear();
+    }
+}
+
+#[cfg(test)]
+mod narrowing_tests {
+    use crate::wordle::Pattern;
+    use super::*;
+
+    fn narrowing() -> Narrowing {
+        Narrowing::new(["slate", "crane", "plate", "about"].iter().map(|word| word.to_string()).collect())
+    }
+
+    fn letters(words: &[&str]) -> LetterProbability {
+        let mut letters = LetterProbability::default();
+        for word in words {
+            letters.add_word(word);
+        }
+        letters
+    }
+
+    #[test]
+    fn apply() {
+        let mut result = narrowing();
+        assert_eq!(result.candidates(&[]).unwrap(), ["about", "crane", "plate", "slate"]);
+
+        let filters = vec![Word::feedback("crane", Pattern::new("crane", "slate"))];
+        result.apply(&filters);
+        assert_eq!(result.candidates(&filters).unwrap(), ["plate", "slate"]);
+        assert_eq!(result.letters(&filters), Some(&letters(&["plate", "slate"])));
+        // the earlier level is still there
+        assert_eq!(result.candidates(&[]).unwrap().len(), 4);
+        assert_eq!(result.candidates(&[Word::feedback("about", Pattern::new("about", "slate"))]), None);
+    }
+
+    #[test]
+    fn undo() {
+        let mut result = narrowing();
+        let filters = vec![
+            Word::feedback("about", Pattern::new("about", "slate")),
+            Word::feedback("plate", Pattern::new("plate", "slate")),
+        ];
+        result.apply(&filters);
+        assert_eq!(result.candidates(&filters).unwrap(), ["slate"]);
+
+        result.apply(&filters[..1]);
+        assert_eq!(result.candidates(&filters[..1]).unwrap(), ["plate", "slate"]);
+        assert_eq!(result.letters(&filters[..1]), Some(&letters(&["plate", "slate"])));
+        assert_eq!(result.candidates(&filters), None);
+    }
+
+    #[test]
+    fn remove() {
+        let mut result = narrowing();
+        result.remove("crane");
+        assert_eq!(result.candidates(&[]).unwrap(), ["about", "plate", "slate"]);
+        assert_eq!(result.letters(&[]), Some(&letters(&["about", "plate", "slate"])));
+
+        result.clear();
+        assert!(result.is_empty());
+        assert_eq!(result.candidates(&[]), None);
+    }
+}
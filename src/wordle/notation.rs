@@ -0,0 +1,146 @@
+use std::error::Error;
+use std::fmt;
+use super::{Board, LetterState, Word, MAX_ATTEMPTS, MAX_LETTERS};
+
+// where board notation stopped making sense, the column counts characters from one
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotationError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl Error for NotationError {}
+
+// a board written as one guess and its feedback per played row, such as crane:..y.g slate:gg..y,
+// with g for green, y for yellow and . for absent
+pub struct Notation;
+
+impl Notation {
+    // rows separated by any whitespace, anything that isn't exactly a five letter word, a colon and
+    // five feedback characters is an error
+    pub fn parse(text: &str) -> Result<Board, NotationError> {
+        let mut board = Board::default();
+        for (row, (column, token)) in Self::tokens(text).into_iter().enumerate() {
+            if row >= MAX_ATTEMPTS {
+                return Err(NotationError { column, message: format!("more than {} rows", MAX_ATTEMPTS) });
+            }
+            board.words[row] = Self::parse_word(token, column)?;
+        }
+        Ok(board)
+    }
+
+    // one guess and its feedback, the column is where the token starts in the whole text
+    fn parse_word(token: &str, column: usize) -> Result<Word, NotationError> {
+        let error = |offset: usize, message: String| NotationError { column: column + offset, message };
+        let Some((guess, feedback)) = token.split_once(':') else {
+            return Err(error(0, format!("'{}' should look like WORD:FEEDBACK", token)));
+        };
+        if let Some((offset, c)) = guess.chars().enumerate().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(error(offset, format!("'{}' isn't a letter", c)));
+        }
+        if guess.len() != MAX_LETTERS {
+            return Err(error(0, format!("'{}' needs {} letters", guess, MAX_LETTERS)));
+        }
+        let start = MAX_LETTERS + 1;
+        let mut word = Word::default();
+        word.set(&guess.to_ascii_lowercase());
+        for (offset, c) in feedback.chars().enumerate() {
+            let state = match c.to_ascii_lowercase() {
+                'g' => LetterState::Correct,
+                'y' => LetterState::Present,
+                '.' => LetterState::Incorrect,
+                _ => return Err(error(start + offset, format!("unknown feedback '{}', expected g, y or .", c))),
+            };
+            if offset >= MAX_LETTERS {
+                return Err(error(start + offset, format!("feedback '{}' has more than {} characters", feedback, MAX_LETTERS)));
+            }
+            word.letters[offset].set_state(state);
+        }
+        if feedback.chars().count() != MAX_LETTERS {
+            return Err(error(start, format!("feedback '{}' needs {} characters", feedback, MAX_LETTERS)));
+        }
+        Ok(word)
+    }
+
+    // the tokens between whitespace with the column each starts at
+    fn tokens(text: &str) -> Vec<(usize, &str)> {
+        let mut tokens = Vec::new();
+        let mut start = None;
+        let mut column = 0;
+        for (idx, c) in text.char_indices() {
+            column += 1;
+            match (c.is_whitespace(), start) {
+                (false, None) => start = Some((column, idx)),
+                (true, Some((from, begin))) => {
+                    tokens.push((from, &text[begin..idx]));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some((from, begin)) = start {
+            tokens.push((from, &text[begin..]));
+        }
+        tokens
+    }
+
+    // every played row, rows still waiting for a guess are left out
+    pub fn print(board: &Board) -> String {
+        board.words.iter()
+            .filter(|word| word.letters.iter().all(|letter| letter.get_state() != LetterState::Disabled))
+            .map(|word| format!("{}:{}", word.value(), word.pattern()))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod notation_tests {
+    use crate::wordle::Pattern;
+    use super::*;
+
+    fn error(column: usize, message: &str) -> Result<Board, NotationError> {
+        Err(NotationError { column, message: message.to_string() })
+    }
+
+    #[test]
+    fn parse() {
+        let result = Notation::parse("crane:..y.g  SLATE:GG..y\n").unwrap();
+        assert_eq!(result.words[0], Word::feedback("crane", Pattern::new("crane", "aisle")));
+        assert_eq!(result.words[0].pattern().to_string(), "..y.g");
+        assert_eq!(result.words[1].value(), "slate");
+        assert_eq!(result.words[1].pattern().to_string(), "gg..y");
+        assert_eq!(result.words[2], Word::default());
+        assert_eq!(Notation::parse("").unwrap(), Board::default());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Notation::parse("crane"), error(1, "'crane' should look like WORD:FEEDBACK"));
+        assert_eq!(Notation::parse("crane:..y.g slate:gg.x."), error(22, "unknown feedback 'x', expected g, y or ."));
+        assert_eq!(Notation::parse("crane:..y.g cr4ne:....."), error(15, "'4' isn't a letter"));
+        assert_eq!(Notation::parse(" cran:....."), error(2, "'cran' needs 5 letters"));
+        assert_eq!(Notation::parse("crane:..y"), error(7, "feedback '..y' needs 5 characters"));
+        assert_eq!(Notation::parse("crane:..y...."), error(12, "feedback '..y....' has more than 5 characters"));
+        assert_eq!(Notation::parse("crane:..g:g"), error(10, "unknown feedback ':', expected g, y or ."));
+        assert_eq!(Notation::parse(&"crane:..... ".repeat(7)), error(73, "more than 6 rows"));
+        // columns count characters rather than bytes
+        assert_eq!(Notation::parse("é:..... crane"), error(1, "'é' isn't a letter"));
+        assert_eq!(Notation::parse("crane:.....\u{3000}\u{3000}slate"), error(14, "'slate' should look like WORD:FEEDBACK"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "crane:..y.g slate:gg..y fixer:ggggg";
+        let board = Notation::parse(text).unwrap();
+        assert_eq!(Notation::print(&board), text);
+        assert_eq!(Notation::parse(&Notation::print(&board)).unwrap(), board);
+        assert_eq!(Notation::print(&Board::default()), "");
+    }
+}
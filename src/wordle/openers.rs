@@ -0,0 +1,199 @@
+use std::fmt;
+use std::path::PathBuf;
+use super::{storage, Position, Progress, Strategy, StrategyKind, MAX_LETTERS};
+
+pub const OPENER_FILE: &str = "opener.txt";
+// the most words that can be pinned to open with
+pub const MAX_OPENER_WORDS: usize = 2;
+// how many guesses are scored between checks for cancelling, each batch sets the strategy up again
+const ANALYSIS_BATCH: usize = 512;
+
+// every first guess ranked by a strategy, for one dictionary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Openers {
+    // the dictionary and strategy the ranking is for
+    key: String,
+    ranked: Vec<(f64, String)>,
+}
+
+impl Openers {
+    // the candidates, their weights, which of them may be suggested and the allowed guesses all
+    // change the ranking
+    pub fn key(position: &Position, strategy: StrategyKind) -> String {
+        let guessable: String = position.guessable.iter().map(|guessable| if *guessable { '1' } else { '0' }).collect();
+        let lines = position.guesses.iter().map(|word| word.to_string())
+            .chain(std::iter::once(String::from("#")))
+            .chain(position.candidates.iter().map(|word| word.to_string()))
+            .chain(position.weights.iter().map(|weight| weight.to_string()))
+            .chain(std::iter::once(guessable));
+        format!("{:016x}-{}", storage::fingerprint(lines), strategy.name())
+    }
+
+    // score every allowed guess, which for a whole dictionary can take a while, giving nothing when
+    // cancelled since part of a ranking would be taken for all of it
+    pub fn analyse_with(position: &Position, strategy: &dyn Strategy, key: &str, progress: &Progress) -> Option<Openers> {
+        progress.start(position.guesses.len());
+        let mut scores: Vec<f64> = Vec::with_capacity(position.guesses.len());
+        for batch in position.guesses.chunks(ANALYSIS_BATCH) {
+            if progress.is_cancelled() {
+                return None;
+            }
+            scores.extend(strategy.scores(position, batch));
+            progress.set(scores.len());
+        }
+        let mut ranked: Vec<(f64, String)> = scores.into_iter().zip(position.guesses.iter().map(|word| word.to_string())).collect();
+        ranked.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        Some(Openers { key: key.to_string(), ranked })
+    }
+
+    // the key on the first line after a #, then a word and its score per line, best first
+    pub fn parse(content: &str) -> Openers {
+        let mut openers = Openers::default();
+        for line in content.lines() {
+            if let Some(key) = line.strip_prefix('#') {
+                openers.key = key.trim().to_string();
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (Some(word), Some(score)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            if let Ok(score) = score.parse::<f64>() {
+                openers.ranked.push((score, word.to_string()));
+            }
+        }
+        openers
+    }
+
+    pub fn path(key: &str) -> PathBuf {
+        storage::data_file(&format!("openers-{}.txt", key))
+    }
+
+    pub fn load(path: &PathBuf) -> std::io::Result<Openers> {
+        Ok(Openers::parse(&std::fs::read_to_string(path)?))
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        storage::write_file(&Self::path(&self.key), &self.to_string())
+    }
+
+    pub fn is_for(&self, key: &str) -> bool {
+        self.key == key
+    }
+
+    pub fn ranked(&self) -> &[(f64, String)] {
+        &self.ranked
+    }
+
+    pub fn best(&self) -> Option<&str> {
+        self.ranked.first().map(|(_, word)| word.as_str())
+    }
+}
+
+impl fmt::Display for Openers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# {}", self.key)?;
+        for (score, word) in self.ranked.iter() {
+            writeln!(f, "{} {}", word, score)?;
+        }
+        Ok(())
+    }
+}
+
+// the words to always open with, a single word or a pair played one after the other
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Opener {
+    words: Vec<String>,
+}
+
+impl Opener {
+    // the words separated by spaces or commas
+    pub fn parse(content: &str) -> Result<Opener, String> {
+        let words: Vec<String> = content.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_lowercase())
+            .collect();
+        if words.len() > MAX_OPENER_WORDS {
+            return Err(format!("an opener is at most {} words", MAX_OPENER_WORDS));
+        }
+        if let Some(word) = words.iter().find(|word| word.len() != MAX_LETTERS || !word.chars().all(|c| c.is_ascii_lowercase())) {
+            return Err(format!("'{}' isn't a {} letter word of a to z", word, MAX_LETTERS));
+        }
+        Ok(Opener { words })
+    }
+
+    pub fn path() -> PathBuf {
+        storage::data_file(OPENER_FILE)
+    }
+
+    pub fn load(path: &PathBuf) -> Result<Opener, Box<dyn std::error::Error>> {
+        Ok(Opener::parse(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &PathBuf) -> std::io::Result<()> {
+        storage::write_file(path, &self.to_string())
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+}
+
+impl fmt::Display for Opener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.words.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod openers_tests {
+    use crate::wordle::Minimax;
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    #[test]
+    fn analyse() {
+        let mut position = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        position.guesses.push(word("fbhkm"));
+        let key = Openers::key(&position, StrategyKind::Minimax);
+        let result = Openers::analyse_with(&position, &Minimax, &key, &Progress::default()).unwrap();
+        assert_eq!(result.best(), Some("fbhkm"));
+        assert_eq!(result.ranked().len(), 6);
+        assert_eq!(result.ranked()[1], (4.0, String::from("bills")));
+        assert_eq!(Openers::parse(&result.to_string()), result);
+        assert!(result.is_for(&key));
+
+        let progress = Progress::default();
+        progress.cancel();
+        assert_eq!(Openers::analyse_with(&position, &Minimax, &key, &progress), None);
+    }
+
+    #[test]
+    fn key() {
+        let position = Position::uniform(&["bills", "fills"]);
+        let key = Openers::key(&position, StrategyKind::Minimax);
+        assert!(key.ends_with("-minimax"));
+        assert_ne!(key, Openers::key(&position, StrategyKind::MostLikely));
+
+        let mut weighted = position.clone();
+        weighted.weights[0] = 2.0;
+        assert_ne!(Openers::key(&weighted, StrategyKind::Minimax), key);
+
+        let mut unwanted = position.clone();
+        unwanted.guessable[0] = false;
+        assert_ne!(Openers::key(&unwanted, StrategyKind::Minimax), key);
+    }
+
+    #[test]
+    fn opener() {
+        assert_eq!(Opener::parse("CRANE").unwrap().words(), ["crane"]);
+        let result = Opener::parse("crane, slate\n").unwrap();
+        assert_eq!(result.words(), ["crane", "slate"]);
+        assert_eq!(Opener::parse(&result.to_string()).unwrap(), result);
+        assert!(Opener::parse("").unwrap().words().is_empty());
+        assert!(Opener::parse("crane slate plate").is_err());
+        assert!(Opener::parse("cranes").is_err());
+        assert!(Opener::parse("cr4ne").is_err());
+        assert!(Opener::parse("cafés").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use super::MAX_LETTERS;
+
+pub const ALPHABET: usize = 26;
+
+// a word as the index of each letter in the alphabet, so it can be copied and compared without
+// allocating, only words of a to z fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct PackedWord {
+    letters: [u8; MAX_LETTERS],
+    len: u8,
+}
+
+impl PackedWord {
+    pub fn new(word: &str) -> Option<PackedWord> {
+        Self::from_chars(word.chars())
+    }
+
+    pub fn from_chars(chars: impl IntoIterator<Item = char>) -> Option<PackedWord> {
+        let mut word = PackedWord::default();
+        for c in chars {
+            if word.len as usize == MAX_LETTERS || !c.is_ascii_lowercase() {
+                return None;
+            }
+            word.letters[word.len as usize] = c as u8 - b'a';
+            word.len += 1;
+        }
+        Some(word)
+    }
+
+    pub fn letters(&self) -> &[u8] {
+        &self.letters[..self.len as usize]
+    }
+
+    pub fn letter_set(&self) -> LetterSet {
+        let mut set = LetterSet::default();
+        for letter in self.letters() {
+            set.insert(*letter);
+        }
+        set
+    }
+}
+
+// the packed form of a word the test knows is valid
+#[cfg(test)]
+pub fn test_word(word: &str) -> PackedWord {
+    PackedWord::new(word).unwrap()
+}
+
+impl fmt::Display for PackedWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for letter in self.letters() {
+            write!(f, "{}", (b'a' + letter) as char)?;
+        }
+        Ok(())
+    }
+}
+
+// which letters of the alphabet a word has, one bit each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LetterSet(u32);
+
+impl LetterSet {
+    pub fn insert(&mut self, letter: u8) {
+        self.0 |= 1 << letter;
+    }
+
+    pub fn contains(&self, letter: u8) -> bool {
+        self.0 & (1 << letter) != 0
+    }
+
+    pub fn letters(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..ALPHABET as u8).filter(|letter| self.contains(*letter))
+    }
+}
+
+#[cfg(test)]
+mod packed_word_tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let result = PackedWord::new("crane").unwrap();
+        assert_eq!(result.letters(), &[2, 17, 0, 13, 4]);
+        assert_eq!(result.to_string(), "crane");
+        assert!(PackedWord::new("abc").unwrap() < result);
+        assert_eq!(PackedWord::new("Crane"), None);
+        assert_eq!(PackedWord::new("cranes"), None);
+    }
+
+    #[test]
+    fn letter_set() {
+        let result = PackedWord::new("geese").unwrap().letter_set();
+        assert!(result.contains(b'e' - b'a'));
+        assert!(!result.contains(b'a' - b'a'));
+        assert_eq!(result.letters().collect::<Vec<u8>>(), vec![4, 6, 18]);
+    }
+}
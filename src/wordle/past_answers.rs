@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use super::{storage, MAX_LETTERS};
+
+pub const PAST_ANSWERS_FILE: &str = "past_answers.txt";
+
+// how much of its score a past answer keeps when down-weighting
+pub const PAST_ANSWER_WEIGHT: f64 = 0.1;
+// the months with five letter names, which are part of a written out date rather than the answer
+const MONTHS: [&str; 2] = ["march", "april"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PastAnswerMode {
+    #[default]
+    Off,
+    // past answers are not candidates at all
+    Exclude,
+    // past answers are still candidates but are guessed only when nothing else scores as well
+    DownWeight,
+}
+
+impl PastAnswerMode {
+    pub const ALL: [PastAnswerMode; 3] = [PastAnswerMode::Off, PastAnswerMode::Exclude, PastAnswerMode::DownWeight];
+
+    // the name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            PastAnswerMode::Off => "off",
+            PastAnswerMode::Exclude => "exclude",
+            PastAnswerMode::DownWeight => "downweight",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<PastAnswerMode> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+impl fmt::Display for PastAnswerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            PastAnswerMode::Off => "Off",
+            PastAnswerMode::Exclude => "Exclude",
+            PastAnswerMode::DownWeight => "Down-weight",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+// previous answers, each with the date it was used if the list had one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PastAnswers {
+    answers: BTreeMap<String, Option<String>>,
+}
+
+impl PastAnswers {
+    // one answer per line, optionally alongside a date separated by whitespace or a comma in either order
+    pub fn parse(content: &str) -> PastAnswers {
+        let mut past_answers = PastAnswers::default();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|field| !field.is_empty())
+                .collect();
+            let Some(idx) = fields.iter().position(|field| Self::is_word(field)) else {
+                continue;
+            };
+            let word = fields[idx].to_lowercase();
+            let date: Vec<&str> = fields.iter().enumerate().filter(|(other, _)| *other != idx).map(|(_, field)| *field).collect();
+            past_answers.answers.insert(word, if date.is_empty() { None } else { Some(date.join(" ")) });
+        }
+        past_answers
+    }
+
+    fn is_word(field: &str) -> bool {
+        field.len() == MAX_LETTERS && field.chars().all(|c| c.is_ascii_alphabetic())
+            && !MONTHS.contains(&field.to_lowercase().as_str())
+    }
+
+    pub fn path() -> PathBuf {
+        storage::data_file(PAST_ANSWERS_FILE)
+    }
+
+    pub fn load(path: &PathBuf) -> std::io::Result<PastAnswers> {
+        Ok(PastAnswers::parse(&std::fs::read_to_string(path)?))
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.answers.contains_key(word)
+    }
+
+    // when the word was the answer, if it was and the list said so
+    pub fn date(&self, word: &str) -> Option<&str> {
+        self.answers.get(word)?.as_deref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+}
+
+#[cfg(test)]
+mod past_answers_tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        let result: PastAnswers = Default::default();
+        assert_eq!(result.len(), 0);
+        assert_eq!(PastAnswerMode::default(), PastAnswerMode::Off);
+    }
+
+    #[test]
+    fn parse() {
+        let result = PastAnswers::parse("# answers\ncigar\n2021-06-20 REBUT\nsissy, 2021-06-21\n\nnot an answer line\n");
+        assert_eq!(result.len(), 3);
+        assert!(result.contains("cigar"));
+        assert!(result.contains("rebut"));
+        assert!(result.contains("sissy"));
+        assert_eq!(result.date("cigar"), None);
+        assert_eq!(result.date("rebut"), Some("2021-06-20"));
+        assert_eq!(result.date("sissy"), Some("2021-06-21"));
+
+        // a month in the date isn't mistaken for the answer, whichever side of it the date is
+        let result = PastAnswers::parse("March 3, 2022 CIGAR
+rebut April 1 2022
+");
+        assert_eq!(result.len(), 2);
+        assert!(!result.contains("march"));
+        assert!(!result.contains("april"));
+        assert_eq!(result.date("cigar"), Some("March 3 2022"));
+        assert_eq!(result.date("rebut"), Some("April 1 2022"));
+    }
+
+    #[test]
+    fn parse_mode() {
+        for mode in PastAnswerMode::ALL {
+            let name = mode.to_string().to_lowercase().replace('-', "");
+            assert_eq!(PastAnswerMode::parse(&name), Some(mode));
+        }
+        assert_eq!(PastAnswerMode::parse("sometimes"), None);
+    }
+}
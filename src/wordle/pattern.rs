@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fmt;
+use super::{LetterState, PackedWord, ALPHABET, MAX_LETTERS};
+
+// the colours the game gives a guess, one base 3 digit per letter with the first letter lowest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pattern(pub u8);
+
+const ABSENT: u8 = 0;
+const PRESENT: u8 = 1;
+const CORRECT: u8 = 2;
+
+impl Pattern {
+    pub const SOLVED: Pattern = Pattern(242);
+
+    // the feedback the game would give for the guess if the answer was the given word, repeated
+    // letters are only yellow as many times as the answer has spare copies of them
+    pub fn new(guess: &str, answer: &str) -> Pattern {
+        match (PackedWord::new(guess), PackedWord::new(answer)) {
+            (Some(guess), Some(answer)) => Self::packed(&guess, &answer),
+            _ => Self::from_chars(guess, answer),
+        }
+    }
+
+    // the same as new without looking at any strings
+    pub fn packed(guess: &PackedWord, answer: &PackedWord) -> Pattern {
+        let mut digits = [ABSENT; MAX_LETTERS];
+        let mut spare = [0u8; ALPHABET];
+        let (guess, answer) = (guess.letters(), answer.letters());
+        for (idx, letter) in answer.iter().enumerate() {
+            if guess.get(idx) == Some(letter) {
+                digits[idx] = CORRECT;
+            } else {
+                spare[*letter as usize] += 1;
+            }
+        }
+        for (idx, letter) in guess.iter().enumerate() {
+            if digits[idx] != CORRECT && spare[*letter as usize] > 0 {
+                digits[idx] = PRESENT;
+                spare[*letter as usize] -= 1;
+            }
+        }
+        Self::from_digits(&digits)
+    }
+
+    // for words that don't pack, letters outside a to z
+    fn from_chars(guess: &str, answer: &str) -> Pattern {
+        let guess: Vec<char> = guess.chars().collect();
+        let mut answer: Vec<Option<char>> = answer.chars().map(Some).collect();
+        let mut digits = [ABSENT; MAX_LETTERS];
+
+        for (idx, c) in guess.iter().enumerate().take(MAX_LETTERS) {
+            if answer.get(idx) == Some(&Some(*c)) {
+                digits[idx] = CORRECT;
+                answer[idx] = None;
+            }
+        }
+        for (idx, c) in guess.iter().enumerate().take(MAX_LETTERS) {
+            if digits[idx] == CORRECT {
+                continue;
+            }
+            if let Some(spare) = answer.iter_mut().find(|spare| **spare == Some(*c)) {
+                digits[idx] = PRESENT;
+                *spare = None;
+            }
+        }
+        Self::from_digits(&digits)
+    }
+
+    pub fn from_states(states: &[LetterState]) -> Pattern {
+        let mut digits = [ABSENT; MAX_LETTERS];
+        for (digit, state) in digits.iter_mut().zip(states.iter()) {
+            *digit = match state {
+                LetterState::Correct => CORRECT,
+                LetterState::Present => PRESENT,
+                _ => ABSENT,
+            };
+        }
+        Self::from_digits(&digits)
+    }
+
+    fn from_digits(digits: &[u8; MAX_LETTERS]) -> Pattern {
+        Pattern(digits.iter().rev().fold(0u8, |acc, digit| acc * 3 + digit))
+    }
+
+    pub fn states(&self) -> [LetterState; MAX_LETTERS] {
+        let mut value = self.0;
+        let mut states: [LetterState; MAX_LETTERS] = Default::default();
+        for state in states.iter_mut() {
+            *state = match value % 3 {
+                CORRECT => LetterState::Correct,
+                PRESENT => LetterState::Present,
+                _ => LetterState::Incorrect,
+            };
+            value /= 3;
+        }
+        states
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::SOLVED
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for state in self.states() {
+            let c = match state {
+                LetterState::Correct => 'g',
+                LetterState::Present => 'y',
+                _ => '.',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+// split the candidates by the feedback the guess would get, each bucket holds candidate indexes
+pub fn partition(guess: &str, candidates: &[String]) -> HashMap<Pattern, Vec<usize>> {
+    let mut buckets: HashMap<Pattern, Vec<usize>> = HashMap::new();
+    for (idx, candidate) in candidates.iter().enumerate() {
+        buckets.entry(Pattern::new(guess, candidate)).or_default().push(idx);
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(Pattern::new("crane", "crane"), Pattern::SOLVED);
+        assert_eq!(Pattern::new("crane", "slate").to_string(), "..g.g");
+        assert_eq!(Pattern::new("crane", "about").to_string(), "..y..");
+        assert_eq!(Pattern::new("fghij", "crane").to_string(), ".....");
+    }
+
+    #[test]
+    fn repeated_letters() {
+        // only one e in the answer so only one e gets colour, greens first
+        assert_eq!(Pattern::new("geese", "those").to_string(), "...gg");
+        assert_eq!(Pattern::new("eerie", "crane").to_string(), "..y.g");
+        assert_eq!(Pattern::new("speed", "abide").to_string(), "..y.y");
+    }
+
+    #[test]
+    fn packed() {
+        for (guess, answer) in [("geese", "those"), ("eerie", "crane"), ("speed", "abide"), ("crane", "crane"), ("abc", "cabde")] {
+            let expected = Pattern::from_chars(guess, answer);
+            assert_eq!(Pattern::packed(&PackedWord::new(guess).unwrap(), &PackedWord::new(answer).unwrap()), expected);
+            assert_eq!(Pattern::new(guess, answer), expected);
+        }
+        // anything outside a to z is still compared
+        assert_eq!(Pattern::new("CRANE", "CRATE").to_string(), "ggg.g");
+    }
+
+    #[test]
+    fn states() {
+        let states = Pattern::new("crane", "slate").states();
+        assert_eq!(states[0], LetterState::Incorrect);
+        assert_eq!(states[2], LetterState::Correct);
+        assert!(Pattern::SOLVED.is_solved());
+        assert_eq!(Pattern::from_states(&states), Pattern::new("crane", "slate"));
+        assert_eq!(Pattern::SOLVED.0 as usize, 3usize.pow(MAX_LETTERS as u32) - 1);
+    }
+
+    #[test]
+    fn partition() {
+        let candidates = vec![String::from("crane"), String::from("slate"), String::from("plate"), String::from("about")];
+        let buckets = super::partition("slate", &candidates);
+        assert_eq!(buckets.len(), 4);
+        assert_eq!(buckets[&Pattern::SOLVED], vec![1]);
+        assert_eq!(buckets[&Pattern::new("slate", "plate")], vec![2]);
+    }
+}
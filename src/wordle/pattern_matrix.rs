@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use memmap2::Mmap;
+use super::{storage, PackedWord, Pattern};
+
+// marks a saved matrix, followed by the version, the row and column counts and the key
+const MAGIC: &[u8; 4] = b"WSPM";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 24;
+
+// the cells of the matrix, worked out here or mapped from a saved file
+enum Table {
+    Built(Vec<u8>),
+    Mapped(Mmap),
+}
+
+// the feedback for every guess against every possible answer, one byte per pair, worked out once
+// per dictionary and saved so it can be mapped straight back in
+pub struct PatternMatrix {
+    key: u64,
+    // looked up by packed word, so only words of a to z have a row or column
+    guesses: HashMap<PackedWord, u32>,
+    answers: HashMap<PackedWord, u32>,
+    columns: usize,
+    table: Table,
+}
+
+impl PatternMatrix {
+    // the guesses and answers in order, both decide the layout of the matrix
+    pub fn key(guesses: &[String], answers: &[String]) -> u64 {
+        let separator = String::from("#");
+        storage::fingerprint(guesses.iter().chain(std::iter::once(&separator)).chain(answers.iter()))
+    }
+
+    pub fn path(key: u64) -> PathBuf {
+        storage::data_file(&format!("patterns-{:016x}.bin", key))
+    }
+
+    // work out every row, split between as many threads as there are cores
+    pub fn build(guesses: &[String], answers: &[String]) -> PatternMatrix {
+        let columns = answers.len();
+        let mut cells = vec![0u8; guesses.len() * columns];
+        let packed: Vec<Option<PackedWord>> = answers.iter().map(|answer| PackedWord::new(answer)).collect();
+        let packed = &packed;
+        if columns > 0 {
+            let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+            let rows_per_thread = guesses.len().div_ceil(threads).max(1);
+            std::thread::scope(|scope| {
+                for (chunk, rows) in cells.chunks_mut(rows_per_thread * columns).zip(guesses.chunks(rows_per_thread)) {
+                    scope.spawn(move || {
+                        for (row, guess) in chunk.chunks_mut(columns).zip(rows) {
+                            let packed_guess = PackedWord::new(guess);
+                            for ((cell, answer), packed_answer) in row.iter_mut().zip(answers).zip(packed) {
+                                *cell = match (&packed_guess, packed_answer) {
+                                    (Some(guess), Some(answer)) => Pattern::packed(guess, answer),
+                                    _ => Pattern::new(guess, answer),
+                                }.0;
+                            }
+                        }
+                    });
+                }
+            });
+        }
+        Self::new(guesses, answers, Table::Built(cells))
+    }
+
+    // map a saved matrix, which has to have been saved for exactly these guesses and answers
+    pub fn load(path: &PathBuf, guesses: &[String], answers: &[String]) -> std::io::Result<PatternMatrix> {
+        let file = File::open(path)?;
+        // matrices are written to a temporary file and renamed into place, so once a file has this
+        // name it is never written to again, anything else changing it while mapped is not guarded
+        let map = unsafe { Mmap::map(&file)? };
+        let invalid = |message: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{}: {}", path.display(), message));
+        if map.len() < HEADER_LEN || &map[0..4] != MAGIC {
+            return Err(invalid("not a pattern matrix"));
+        }
+        let number = |start: usize| u32::from_le_bytes(map[start..start + 4].try_into().unwrap());
+        if number(4) != VERSION {
+            return Err(invalid("saved by a different version"));
+        }
+        let key = u64::from_le_bytes(map[16..HEADER_LEN].try_into().unwrap());
+        if number(8) as usize != guesses.len() || number(12) as usize != answers.len() || key != Self::key(guesses, answers) {
+            return Err(invalid("saved for a different dictionary"));
+        }
+        if map.len() != HEADER_LEN + guesses.len() * answers.len() {
+            return Err(invalid("wrong size"));
+        }
+        Ok(Self::new(guesses, answers, Table::Mapped(map)))
+    }
+
+    // written beside the path first and renamed over it, so a matrix mapped from the path is never
+    // truncated under it and a half written file is never mapped
+    pub fn save(&self, path: &PathBuf) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut temp = path.clone().into_os_string();
+        temp.push(format!(".{}.tmp", std::process::id()));
+        let temp = PathBuf::from(temp);
+        let written = self.write(&temp).and_then(|()| std::fs::rename(&temp, path));
+        if written.is_err() {
+            let _ = std::fs::remove_file(&temp);
+        }
+        written
+    }
+
+    fn write(&self, path: &PathBuf) -> std::io::Result<()> {
+        let mut file = File::create(path)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        file.write_all(&(self.guesses.len() as u32).to_le_bytes())?;
+        file.write_all(&(self.columns as u32).to_le_bytes())?;
+        file.write_all(&self.key.to_le_bytes())?;
+        file.write_all(self.cells())?;
+        file.sync_all()
+    }
+
+    // the saved matrix for the words, building and saving it first when there isn't one, a matrix
+    // that couldn't be saved is still used and why it couldn't be saved given alongside it
+    pub fn open(guesses: &[String], answers: &[String]) -> (PatternMatrix, std::io::Result<()>) {
+        Self::open_at(&Self::path(Self::key(guesses, answers)), guesses, answers)
+    }
+
+    fn open_at(path: &PathBuf, guesses: &[String], answers: &[String]) -> (PatternMatrix, std::io::Result<()>) {
+        if let Ok(matrix) = Self::load(path, guesses, answers) {
+            return (matrix, Ok(()));
+        }
+        let matrix = Self::build(guesses, answers);
+        let saved = matrix.save(path);
+        (matrix, saved)
+    }
+
+    fn new(guesses: &[String], answers: &[String], table: Table) -> PatternMatrix {
+        let index = |words: &[String]| words.iter().enumerate()
+            .filter_map(|(idx, word)| PackedWord::new(word).map(|word| (word, idx as u32)))
+            .collect();
+        PatternMatrix {
+            key: Self::key(guesses, answers),
+            guesses: index(guesses),
+            answers: index(answers),
+            columns: answers.len(),
+            table,
+        }
+    }
+
+    fn cells(&self) -> &[u8] {
+        match &self.table {
+            Table::Built(cells) => cells,
+            Table::Mapped(map) => &map[HEADER_LEN..],
+        }
+    }
+
+    // the feedback the guess gets against every answer, in column order
+    pub fn row(&self, guess: &PackedWord) -> Option<&[u8]> {
+        let row = *self.guesses.get(guess)? as usize;
+        Some(&self.cells()[row * self.columns..(row + 1) * self.columns])
+    }
+
+    pub fn column(&self, answer: &PackedWord) -> Option<u32> {
+        self.answers.get(answer).copied()
+    }
+}
+
+impl fmt::Debug for PatternMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PatternMatrix({:016x}, {}x{})", self.key, self.guesses.len(), self.columns)
+    }
+}
+
+// the matrix along with the column of each of a position's candidates, in the same order
+#[derive(Debug, Clone)]
+pub struct MatrixLookup {
+    matrix: Arc<PatternMatrix>,
+    columns: Vec<u32>,
+}
+
+impl MatrixLookup {
+    // only when every candidate has a column, otherwise the patterns are worked out as needed
+    pub fn new(matrix: &Arc<PatternMatrix>, candidates: &[PackedWord]) -> Option<MatrixLookup> {
+        let columns = candidates.iter().map(|candidate| matrix.column(candidate)).collect::<Option<Vec<u32>>>()?;
+        Some(MatrixLookup { matrix: matrix.clone(), columns })
+    }
+
+    // the lookup for the given candidates, by index
+    pub fn narrow(&self, candidates: &[usize]) -> MatrixLookup {
+        MatrixLookup {
+            matrix: self.matrix.clone(),
+            columns: candidates.iter().map(|idx| self.columns[*idx]).collect(),
+        }
+    }
+
+    // the feedback the guess gets against each candidate, if the guess has a row
+    pub fn patterns(&self, guess: &PackedWord) -> Option<Vec<Pattern>> {
+        let row = self.matrix.row(guess)?;
+        Some(self.columns.iter().map(|column| Pattern(row[*column as usize])).collect())
+    }
+}
+
+impl PartialEq for MatrixLookup {
+    fn eq(&self, other: &Self) -> bool {
+        self.matrix.key == other.matrix.key && self.columns == other.columns
+    }
+}
+
+#[cfg(test)]
+mod pattern_matrix_tests {
+    use crate::wordle::test_word as packed;
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn build() {
+        let guesses = words(&["crane", "geese", "slate"]);
+        let answers = words(&["abide", "slate", "those"]);
+        let result = PatternMatrix::build(&guesses, &answers);
+        for guess in guesses.iter() {
+            let row = result.row(&packed(guess)).unwrap();
+            for answer in answers.iter() {
+                assert_eq!(Pattern(row[result.column(&packed(answer)).unwrap() as usize]), Pattern::new(guess, answer));
+            }
+        }
+        assert_eq!(result.row(&packed("fuzzy")), None);
+        assert_eq!(result.column(&packed("crane")), None);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let guesses = words(&["crane", "slate"]);
+        let answers = words(&["slate", "those"]);
+        let path = std::env::temp_dir().join("wordle_solve_pattern_matrix_tests.bin");
+        PatternMatrix::build(&guesses, &answers).save(&path).unwrap();
+
+        let result = PatternMatrix::load(&path, &guesses, &answers).unwrap();
+        assert_eq!(result.row(&packed("crane")).unwrap(), &[Pattern::new("crane", "slate").0, Pattern::new("crane", "those").0]);
+        // a matrix for other words is never used
+        assert!(PatternMatrix::load(&path, &answers, &guesses).is_err());
+
+        // saving again replaces the file rather than writing over the one already mapped
+        PatternMatrix::build(&answers, &guesses).save(&path).unwrap();
+        assert_eq!(result.row(&packed("crane")).unwrap(), &[Pattern::new("crane", "slate").0, Pattern::new("crane", "those").0]);
+        assert!(PatternMatrix::load(&path, &answers, &guesses).is_ok());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn open() {
+        let guesses = words(&["crane", "slate"]);
+        let answers = words(&["slate", "those"]);
+        let path = std::env::temp_dir().join("wordle_solve_pattern_matrix_open_tests.bin");
+        let _ = std::fs::remove_file(&path);
+        let (result, saved) = PatternMatrix::open_at(&path, &guesses, &answers);
+        assert!(saved.is_ok());
+        assert!(matches!(result.table, Table::Built(_)));
+        let (result, saved) = PatternMatrix::open_at(&path, &guesses, &answers);
+        assert!(saved.is_ok());
+        assert!(matches!(result.table, Table::Mapped(_)));
+
+        // a matrix that can't be saved is still built, a file can't be a directory
+        let (result, saved) = PatternMatrix::open_at(&path.join("matrix.bin"), &guesses, &answers);
+        assert!(saved.is_err());
+        assert_eq!(result.row(&packed("crane")).unwrap(), &[Pattern::new("crane", "slate").0, Pattern::new("crane", "those").0]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn lookup() {
+        let words = words(&["crane", "slate", "those"]);
+        let matrix = Arc::new(PatternMatrix::build(&words, &words));
+        let candidates = vec![packed("those"), packed("slate")];
+        let result = MatrixLookup::new(&matrix, &candidates).unwrap();
+        assert_eq!(result.patterns(&packed("slate")), Some(vec![Pattern::new("slate", "those"), Pattern::SOLVED]));
+        assert_eq!(result.narrow(&[1]).patterns(&packed("crane")), Some(vec![Pattern::new("crane", "slate")]));
+        assert!(MatrixLookup::new(&matrix, &[packed("fuzzy")]).is_none());
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use super::storage;
+
+pub const PRIORS_FILE: &str = "priors.tsv";
+
+// how likely each word is to be the answer, from a word frequency list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Priors {
+    weights: HashMap<String, f64>,
+    // words missing from the list are taken to be as rare as the rarest word on it
+    floor: f64,
+}
+
+impl Priors {
+    // one word per line followed by a count or probability, separated by a tab, comma or spaces,
+    // lines without a number such as a header are skipped
+    pub fn parse(content: &str) -> Priors {
+        let mut priors = Priors::default();
+        for line in content.lines() {
+            let mut fields = line.split(['\t', ',', ' '])
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty());
+            let (Some(word), Some(value)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f64>() else {
+                continue;
+            };
+            if value.is_finite() && value > 0.0 {
+                priors.weights.insert(word.to_lowercase(), value);
+            }
+        }
+        priors.floor = priors.weights.values().cloned().fold(f64::INFINITY, f64::min);
+        priors
+    }
+
+    pub fn path() -> PathBuf {
+        storage::data_file(PRIORS_FILE)
+    }
+
+    pub fn load(path: &PathBuf) -> std::io::Result<Priors> {
+        Ok(Priors::parse(&std::fs::read_to_string(path)?))
+    }
+
+    // the relative weight of the word, every word weighs the same when there is no list
+    pub fn weight(&self, word: &str) -> f64 {
+        if self.weights.is_empty() {
+            return 1.0;
+        }
+        self.weights.get(word).copied().unwrap_or(self.floor)
+    }
+
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+}
+
+#[cfg(test)]
+mod priors_tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        let result: Priors = Default::default();
+        assert_eq!(result.len(), 0);
+        assert_eq!(result.weight("xylyl"), 1.0);
+    }
+
+    #[test]
+    fn parse() {
+        let result = Priors::parse("word\tcount\nhouse\t1000\nxylyl,2\nCRANE 0.5\nbogus\tmany\nzero\t0\n");
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.weight("house"), 1000.0);
+        assert_eq!(result.weight("xylyl"), 2.0);
+        assert_eq!(result.weight("crane"), 0.5);
+        assert_eq!(result.weight("slate"), 0.5);
+    }
+}
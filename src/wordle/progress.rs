@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+// shared with a long computation to see how far it has got and to ask it to stop early, once
+// stopped it gives back the best it had found so far
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    cancelled: Arc<AtomicBool>,
+    done: Arc<AtomicUsize>,
+    total: Arc<AtomicUsize>,
+}
+
+impl Progress {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    // a new stage of the work with this many steps
+    pub fn start(&self, total: usize) {
+        self.done.store(0, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn step(&self) {
+        self.done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, done: usize) {
+        self.done.store(done, Ordering::Relaxed);
+    }
+
+    // how much of the current stage is done, from 0 to 1
+    pub fn fraction(&self) -> f32 {
+        let total = self.total.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+        (self.done.load(Ordering::Relaxed) as f32 / total as f32).min(1.0)
+    }
+}
+
+// a computation running on its own thread, checked on every so often for its result
+#[derive(Debug)]
+pub struct Task<T> {
+    progress: Progress,
+    result: Arc<Mutex<Option<T>>>,
+}
+
+// clones share the one result, so the result itself needn't be cloneable
+impl<T> Clone for Task<T> {
+    fn clone(&self) -> Self {
+        Task { progress: self.progress.clone(), result: self.result.clone() }
+    }
+}
+
+impl<T: Send + 'static> Task<T> {
+    pub fn spawn(work: impl FnOnce(&Progress) -> T + Send + 'static) -> Task<T> {
+        let task = Task { progress: Progress::default(), result: Arc::new(Mutex::new(None)) };
+        let (progress, result) = (task.progress.clone(), task.result.clone());
+        std::thread::spawn(move || {
+            let value = work(&progress);
+            *result.lock().unwrap() = Some(value);
+        });
+        task
+    }
+
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    // the result once the computation has finished, it is only handed out once
+    pub fn take(&self) -> Option<T> {
+        self.result.lock().unwrap().take()
+    }
+}
+
+#[cfg(test)]
+mod progress_tests {
+    use std::time::Duration;
+    use super::*;
+
+    #[test]
+    fn progress() {
+        let result = Progress::default();
+        assert_eq!(result.fraction(), 0.0);
+        result.start(4);
+        result.step();
+        assert_eq!(result.fraction(), 0.25);
+        result.set(8);
+        assert_eq!(result.fraction(), 1.0);
+
+        let shared = result.clone();
+        assert!(!shared.is_cancelled());
+        result.cancel();
+        assert!(shared.is_cancelled());
+    }
+
+    #[test]
+    fn task() {
+        let result = Task::spawn(|progress| {
+            while !progress.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            "stopped"
+        });
+        assert_eq!(result.take(), None);
+        result.progress().cancel();
+        let mut value = None;
+        for _ in 0..1000 {
+            value = result.take();
+            if value.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(value, Some("stopped"));
+        assert_eq!(result.take(), None);
+    }
+}
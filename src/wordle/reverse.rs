@@ -0,0 +1,129 @@
+use super::{LetterState, PackedWord, Pattern, ALPHABET};
+
+// the words that could have been played in one row of a grid with a known answer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReverseRow {
+    pub pattern: Pattern,
+    // every word that gives the row's colours against the answer
+    pub words: Vec<String>,
+    // the ones that also fit a hard mode game with the other rows, empty when no hard mode game
+    // could have given the grid
+    pub hard_words: Vec<String>,
+}
+
+// the guesses behind a grid of colours, worked out backwards from the answer
+pub struct Reverse;
+
+impl Reverse {
+    // the words for each row of the grid, rows after a solved row are ignored
+    pub fn solve(answer: &str, patterns: &[Pattern], guesses: &[String]) -> Vec<ReverseRow> {
+        let mut rows: Vec<ReverseRow> = Vec::new();
+        for pattern in patterns.iter() {
+            let words: Vec<String> = if pattern.is_solved() {
+                vec![answer.to_string()]
+            } else {
+                guesses.iter().filter(|guess| Pattern::new(guess, answer) == *pattern && *guess != answer).cloned().collect()
+            };
+            rows.push(ReverseRow { pattern: *pattern, hard_words: words.clone(), words });
+            if pattern.is_solved() {
+                break;
+            }
+        }
+        Self::narrow_hard(&mut rows);
+        rows
+    }
+
+    // keep only words that some word in every other row agrees with, until nothing changes
+    fn narrow_hard(rows: &mut [ReverseRow]) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for idx in 0..rows.len() {
+                let kept: Vec<String> = rows[idx].hard_words.iter().filter(|word| {
+                    rows[..idx].iter().all(|earlier| earlier.hard_words.iter().any(|guess| Self::follows_hard_mode(guess, earlier.pattern, word)))
+                        && rows[idx + 1..].iter().all(|later| later.hard_words.iter().any(|guess| Self::follows_hard_mode(word, rows[idx].pattern, guess)))
+                }).cloned().collect();
+                if kept.len() != rows[idx].hard_words.len() {
+                    rows[idx].hard_words = kept;
+                    changed = true;
+                }
+            }
+        }
+        // a row with nothing left means the game wasn't played in hard mode
+        if rows.iter().any(|row| row.hard_words.is_empty()) {
+            for row in rows.iter_mut() {
+                row.hard_words.clear();
+            }
+        }
+    }
+
+    // whether a later guess uses every hint an earlier guess got, greens in the same place and
+    // every green or yellow letter at least as many times
+    pub fn follows_hard_mode(earlier: &str, pattern: Pattern, later: &str) -> bool {
+        let (Some(earlier), Some(later)) = (PackedWord::new(earlier), PackedWord::new(later)) else {
+            return false;
+        };
+        let mut needed = [0u8; ALPHABET];
+        for (idx, (letter, state)) in earlier.letters().iter().zip(pattern.states()).enumerate() {
+            match state {
+                LetterState::Correct if later.letters().get(idx) != Some(letter) => return false,
+                LetterState::Correct | LetterState::Present => needed[*letter as usize] += 1,
+                _ => {}
+            }
+        }
+        for letter in later.letters() {
+            needed[*letter as usize] = needed[*letter as usize].saturating_sub(1);
+        }
+        needed.iter().all(|count| *count == 0)
+    }
+}
+
+#[cfg(test)]
+mod reverse_tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn follows_hard_mode() {
+        let pattern = Pattern::new("crane", "slate");
+        assert!(Reverse::follows_hard_mode("crane", pattern, "plate"));
+        // the a has to stay where it was green
+        assert!(!Reverse::follows_hard_mode("crane", pattern, "aloes"));
+        let pattern = Pattern::new("least", "slate");
+        assert!(Reverse::follows_hard_mode("least", pattern, "slate"));
+        assert!(!Reverse::follows_hard_mode("least", pattern, "slant"));
+    }
+
+    #[test]
+    fn solve() {
+        let guesses = words(&["crane", "drake", "flute", "plate", "slate"]);
+        let patterns = [Pattern::new("crane", "slate"), Pattern::new("plate", "slate"), Pattern::SOLVED, Pattern::SOLVED];
+        let result = Reverse::solve("slate", &patterns, &guesses);
+        assert_eq!(result.len(), 3);
+        // crane and drake both give ..g.g
+        assert_eq!(result[0].words, ["crane", "drake"]);
+        assert_eq!(result[1].words, ["plate"]);
+        assert_eq!(result[2].words, ["slate"]);
+        assert_eq!(result[0].hard_words, ["crane", "drake"]);
+    }
+
+    #[test]
+    fn hard_mode() {
+        let guesses = words(&["crane", "haste", "lucky", "slate", "tonic"]);
+        // lucky and tonic both give y...., but the l of lucky would have to be in haste
+        let patterns = [Pattern::new("tonic", "slate"), Pattern::new("haste", "slate"), Pattern::SOLVED];
+        let result = Reverse::solve("slate", &patterns, &guesses);
+        assert_eq!(result[0].words, ["lucky", "tonic"]);
+        assert_eq!(result[0].hard_words, ["tonic"]);
+        assert_eq!(result[1].hard_words, ["haste"]);
+
+        // tonic drops the a crane found, so this was never a hard mode game
+        let patterns = [Pattern::new("crane", "slate"), Pattern::new("tonic", "slate")];
+        let result = Reverse::solve("slate", &patterns, &guesses);
+        assert_eq!(result[1].words, ["lucky", "tonic"]);
+        assert!(result.iter().all(|row| row.hard_words.is_empty()));
+    }
+}
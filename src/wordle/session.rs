@@ -0,0 +1,201 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+use super::{storage, Blocklist, Board, DecisionTree, EndgameGoal, Lookahead, Notation, PastAnswerMode, PastAnswers,
+            Priors, StrategyKind, DEFAULT_DICTIONARY_URL, DEFAULT_ENDGAME_THRESHOLD};
+
+pub const SESSION_FILE: &str = "session.txt";
+pub const SAVED_SESSION_FILE: &str = "saved-session.txt";
+
+// everything needed to pick a game back up where it was left, the board, where the dictionary came
+// from, the settings and the rejected words
+//
+// kept in a text file of its own rather than eframe's storage, which needs the persistence feature
+// and ron with it, and so it can be saved anywhere and opened again
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub words_url: String,
+    // whether the dictionary had been downloaded, so it is downloaded again on restore
+    pub downloaded: bool,
+    pub board: Board,
+    pub strategy: StrategyKind,
+    pub lookahead: Lookahead,
+    pub endgame_threshold: usize,
+    pub endgame_goal: EndgameGoal,
+    pub past_answer_mode: PastAnswerMode,
+    pub past_answers_path: String,
+    pub priors_path: String,
+    pub tree_path: String,
+    pub blocklist: Blocklist,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            words_url: DEFAULT_DICTIONARY_URL.to_owned(),
+            downloaded: false,
+            board: Board::default(),
+            strategy: StrategyKind::default(),
+            lookahead: Lookahead::default(),
+            endgame_threshold: DEFAULT_ENDGAME_THRESHOLD,
+            endgame_goal: EndgameGoal::default(),
+            past_answer_mode: PastAnswerMode::default(),
+            past_answers_path: PastAnswers::path().to_string_lossy().to_string(),
+            priors_path: Priors::path().to_string_lossy().to_string(),
+            tree_path: DecisionTree::path().to_string_lossy().to_string(),
+            blocklist: Blocklist::default(),
+        }
+    }
+}
+
+impl Session {
+    // one setting per line as a name and its value, the board in board notation and a reject line
+    // per rejected word, anything left out keeps its default
+    pub fn parse(content: &str) -> Result<Session, String> {
+        let mut session = Session::default();
+        let mut rejected = String::new();
+        for (idx, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line.split_once(' ').unwrap_or((line, ""));
+            session.parse_line(name, value, &mut rejected).map_err(|e| format!("line {}: {}", idx + 1, e))?;
+        }
+        session.blocklist = Blocklist::parse(&rejected);
+        Ok(session)
+    }
+
+    fn parse_line(&mut self, name: &str, value: &str, rejected: &mut String) -> Result<(), String> {
+        match name {
+            "dictionary" => self.words_url = value.to_string(),
+            "downloaded" => self.downloaded = match value {
+                "yes" => true,
+                "no" => false,
+                _ => return Err(format!("downloaded is yes or no, not '{}'", value)),
+            },
+            "board" => self.board = Notation::parse(value).map_err(|e| e.to_string())?,
+            "strategy" => self.strategy = StrategyKind::parse(value).ok_or(format!("unknown strategy '{}'", value))?,
+            "lookahead" => {
+                let (breadth, seconds) = value.split_once(' ').ok_or(format!("'{}' should be a breadth and seconds", value))?;
+                self.lookahead.breadth = breadth.parse().map_err(|_| format!("'{}' isn't a whole number", breadth))?;
+                self.lookahead.time_limit = seconds.parse().ok().and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                    .ok_or(format!("'{}' isn't a number of seconds", seconds))?;
+            }
+            "endgame" => {
+                let (threshold, goal) = value.split_once(' ').ok_or(format!("'{}' should be a threshold and goal", value))?;
+                self.endgame_threshold = threshold.parse().map_err(|_| format!("'{}' isn't a whole number", threshold))?;
+                self.endgame_goal = EndgameGoal::parse(goal).ok_or(format!("unknown endgame goal '{}'", goal))?;
+            }
+            "past-answers-mode" => {
+                self.past_answer_mode = PastAnswerMode::parse(value).ok_or(format!("unknown past answers mode '{}'", value))?;
+            }
+            "past-answers" => self.past_answers_path = value.to_string(),
+            "priors" => self.priors_path = value.to_string(),
+            "tree" => self.tree_path = value.to_string(),
+            "reject" => {
+                rejected.push_str(value);
+                rejected.push('\n');
+            }
+            _ => return Err(format!("unknown setting '{}'", name)),
+        }
+        Ok(())
+    }
+
+    // the session saved on every change and restored on launch
+    pub fn path() -> PathBuf {
+        storage::data_file(SESSION_FILE)
+    }
+
+    // where a session is saved by name unless another file is picked
+    pub fn saved_path() -> PathBuf {
+        storage::data_file(SAVED_SESSION_FILE)
+    }
+
+    pub fn load(path: &PathBuf) -> Result<Session, Box<dyn std::error::Error>> {
+        Ok(Session::parse(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &PathBuf) -> std::io::Result<()> {
+        storage::write_file(path, &self.to_string())
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# wordle_solve session")?;
+        writeln!(f, "dictionary {}", self.words_url)?;
+        writeln!(f, "downloaded {}", if self.downloaded { "yes" } else { "no" })?;
+        writeln!(f, "board {}", Notation::print(&self.board))?;
+        writeln!(f, "strategy {}", self.strategy.name())?;
+        writeln!(f, "lookahead {} {}", self.lookahead.breadth, self.lookahead.time_limit.as_secs_f64())?;
+        writeln!(f, "endgame {} {}", self.endgame_threshold, self.endgame_goal.name())?;
+        writeln!(f, "past-answers-mode {}", self.past_answer_mode.name())?;
+        writeln!(f, "past-answers {}", self.past_answers_path)?;
+        writeln!(f, "priors {}", self.priors_path)?;
+        writeln!(f, "tree {}", self.tree_path)?;
+        for line in self.blocklist.to_string().lines() {
+            writeln!(f, "reject {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod session_tests {
+    use crate::wordle::RejectReason;
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let result = Session::parse("# a comment\ndictionary words.txt\ndownloaded yes\nboard crane:..y.g\nstrategy minimax\n\
+                                     lookahead 4 0.5\nendgame 20 solves\npast-answers-mode exclude\npriors my priors.txt\n\
+                                     reject fjord unwanted\nreject xylyl\n").unwrap();
+        assert_eq!(result.words_url, "words.txt");
+        assert!(result.downloaded);
+        assert_eq!(Notation::print(&result.board), "crane:..y.g");
+        assert_eq!(result.strategy, StrategyKind::Minimax);
+        assert_eq!(result.lookahead.breadth, 4);
+        assert_eq!(result.lookahead.time_limit, Duration::from_millis(500));
+        assert_eq!(result.endgame_threshold, 20);
+        assert_eq!(result.endgame_goal, EndgameGoal::MostSolves);
+        assert_eq!(result.past_answer_mode, PastAnswerMode::Exclude);
+        assert_eq!(result.priors_path, "my priors.txt");
+        // left out, so still the default
+        assert_eq!(result.tree_path, Session::default().tree_path);
+        assert_eq!(result.blocklist.get("fjord"), Some(RejectReason::Unwanted));
+        assert_eq!(result.blocklist.get("xylyl"), Some(RejectReason::NotAccepted));
+        assert_eq!(Session::parse("").unwrap(), Session::default());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Session::parse("strategy psychic"), Err(String::from("line 1: unknown strategy 'psychic'")));
+        assert_eq!(Session::parse("\nboard crane:..x.g"), Err(String::from("line 2: column 9: unknown feedback 'x', expected g, y or .")));
+        assert_eq!(Session::parse("colour blue"), Err(String::from("line 1: unknown setting 'colour'")));
+        assert!(Session::parse("downloaded maybe").is_err());
+        assert!(Session::parse("lookahead 4").is_err());
+        assert!(Session::parse("lookahead 4 -1").is_err());
+        assert!(Session::parse("endgame lots guesses").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut session = Session {
+            words_url: String::from("https://example.com/words"),
+            downloaded: true,
+            board: Notation::parse("crane:..y.g slate:gg..y").unwrap(),
+            strategy: StrategyKind::Lookahead,
+            endgame_goal: EndgameGoal::MostSolves,
+            past_answer_mode: PastAnswerMode::DownWeight,
+            ..Default::default()
+        };
+        session.lookahead.time_limit = Duration::from_millis(2_500);
+        session.blocklist.insert("fjord", RejectReason::Unwanted);
+        assert_eq!(Session::parse(&session.to_string()).unwrap(), session);
+
+        let path = std::env::temp_dir().join("wordle_solve_session_tests").join(SESSION_FILE);
+        session.save(&path).unwrap();
+        assert_eq!(Session::load(&path).unwrap(), session);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+use super::{Board, LetterState, Pattern, Word, MAX_ATTEMPTS, MAX_LETTERS};
+
+// the days from 1970-01-01 to the first Wordle, 2021-06-19, which was number 0
+const FIRST_PUZZLE_DAY: u64 = 18_797;
+// the emoji presentation selector some apps put after the black and white squares
+const VARIATION_SELECTOR: char = '\u{fe0f}';
+
+// one row of a share grid, with the guess when it was pasted alongside the squares
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareRow {
+    pub pattern: Pattern,
+    pub guess: Option<String>,
+}
+
+// the block of coloured squares the game gives to share a result, `Wordle 1,234 4/6` followed by
+// one row of squares per guess
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShareGrid {
+    // the puzzle number as written, such as 1,234
+    pub number: Option<String>,
+    pub hard: bool,
+    pub rows: Vec<ShareRow>,
+}
+
+impl ShareGrid {
+    // a pasted share block, or just its rows, with or without the guess beside each row, squares
+    // may be the normal or the high contrast colours
+    pub fn parse(text: &str) -> Result<ShareGrid, String> {
+        Self::parse_lines(text.lines().enumerate())
+    }
+
+    // several share blocks pasted one after another, each starting with its Wordle header
+    pub fn parse_all(text: &str) -> Result<Vec<ShareGrid>, String> {
+        let mut blocks: Vec<Vec<(usize, &str)>> = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            if blocks.is_empty() || line.trim().starts_with("Wordle") {
+                blocks.push(Vec::new());
+            }
+            blocks.last_mut().unwrap().push((idx, line));
+        }
+        // text before the first header is only a block of its own if it has something in it
+        blocks.retain(|block| block.iter().any(|(_, line)| !line.trim().is_empty()));
+        if blocks.is_empty() {
+            return Err("no rows of squares".to_string());
+        }
+        blocks.into_iter().map(|block| Self::parse_lines(block.into_iter())).collect()
+    }
+
+    fn parse_lines<'a>(lines: impl Iterator<Item = (usize, &'a str)>) -> Result<ShareGrid, String> {
+        let mut grid = ShareGrid::default();
+        for (idx, line) in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {}", idx + 1, message);
+            if let Some(header) = line.strip_prefix("Wordle") {
+                if !grid.rows.is_empty() {
+                    return Err(error("the Wordle header has to come before the rows".to_string()));
+                }
+                grid.parse_header(header).map_err(error)?;
+                continue;
+            }
+            grid.rows.push(Self::parse_row(line).map_err(error)?);
+        }
+        if grid.rows.is_empty() {
+            return Err("no rows of squares".to_string());
+        }
+        Ok(grid)
+    }
+
+    fn parse_header(&mut self, header: &str) -> Result<(), String> {
+        let mut fields = header.split_whitespace();
+        let number = fields.next().ok_or("the Wordle header has no puzzle number")?;
+        if !number.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.') {
+            return Err(format!("'{}' isn't a puzzle number", number));
+        }
+        self.number = Some(number.to_string());
+        if let Some(score) = fields.next() {
+            let score = match score.strip_suffix('*') {
+                Some(score) => {
+                    self.hard = true;
+                    score
+                }
+                None => score,
+            };
+            let valid = score.split_once('/').is_some_and(|(guesses, attempts)| {
+                (guesses == "X" || guesses.parse::<usize>().is_ok()) && attempts.parse::<usize>().is_ok()
+            });
+            if !valid {
+                return Err(format!("'{}' isn't a score like 4/6", score));
+            }
+        }
+        Ok(())
+    }
+
+    // the squares, with the guess before or after them
+    fn parse_row(line: &str) -> Result<ShareRow, String> {
+        let mut states = Vec::new();
+        let mut guess = String::new();
+        for c in line.chars() {
+            match c {
+                '🟩' | '🟧' => states.push(LetterState::Correct),
+                '🟨' | '🟦' => states.push(LetterState::Present),
+                '⬛' | '⬜' => states.push(LetterState::Incorrect),
+                VARIATION_SELECTOR => {}
+                c if c.is_whitespace() || c == ':' => {}
+                c if c.is_alphabetic() => guess.extend(c.to_lowercase()),
+                _ => return Err(format!("unexpected '{}'", c)),
+            }
+        }
+        if states.len() != MAX_LETTERS {
+            return Err(format!("{} squares instead of {}", states.len(), MAX_LETTERS));
+        }
+        if !guess.is_empty() && guess.chars().count() != MAX_LETTERS {
+            return Err(format!("the guess '{}' needs {} letters", guess, MAX_LETTERS));
+        }
+        Ok(ShareRow { pattern: Pattern::from_states(&states), guess: if guess.is_empty() { None } else { Some(guess) } })
+    }
+
+    // every row played on the board
+    pub fn from_board(board: &Board, number: Option<String>) -> ShareGrid {
+        let rows = board.words.iter()
+            .filter(|word| !word.letters.iter().any(|letter| letter.get_state() == LetterState::Disabled))
+            .map(|word| ShareRow { pattern: word.pattern(), guess: Some(word.value()) })
+            .collect();
+        ShareGrid { number, hard: false, rows }
+    }
+
+    // the board the grid was played on, which needs the guess for every row
+    pub fn board(&self) -> Result<Board, String> {
+        if self.rows.len() > MAX_ATTEMPTS {
+            return Err(format!("{} rows is more than the {} attempts", self.rows.len(), MAX_ATTEMPTS));
+        }
+        let mut board = Board::default();
+        for (idx, row) in self.rows.iter().enumerate() {
+            let guess = row.guess.as_ref().ok_or(format!("row {} has no guess beside it", idx + 1))?;
+            board.words[idx] = Word::feedback(guess, row.pattern);
+        }
+        Ok(board)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.rows.last().is_some_and(|row| row.pattern.is_solved())
+    }
+
+    // the puzzle number of today's Wordle, written the way the game writes it
+    pub fn today_number() -> String {
+        let days = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() / 86_400);
+        Self::number_for(days)
+    }
+
+    fn number_for(days: u64) -> String {
+        let digits = days.saturating_sub(FIRST_PUZZLE_DAY).to_string();
+        let mut number = String::new();
+        for (idx, c) in digits.chars().enumerate() {
+            if idx > 0 && (digits.len() - idx) % 3 == 0 {
+                number.push(',');
+            }
+            number.push(c);
+        }
+        number
+    }
+
+    // the share block the game would give, high contrast swaps green and yellow for orange and blue
+    pub fn render(&self, high_contrast: bool) -> String {
+        let score = if self.is_solved() { self.rows.len().to_string() } else { "X".to_string() };
+        let mut text = String::from("Wordle");
+        if let Some(number) = &self.number {
+            let _ = write!(text, " {}", number);
+        }
+        let _ = writeln!(text, " {}/{}{}", score, MAX_ATTEMPTS, if self.hard { "*" } else { "" });
+        text.push('\n');
+        for row in self.rows.iter() {
+            for state in row.pattern.states() {
+                text.push(match (state, high_contrast) {
+                    (LetterState::Correct, false) => '🟩',
+                    (LetterState::Correct, true) => '🟧',
+                    (LetterState::Present, false) => '🟨',
+                    (LetterState::Present, true) => '🟦',
+                    _ => '⬛',
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod share_tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let result = ShareGrid::parse("Wordle 1,234 3/6*\n\n⬛🟨⬛⬛🟩\n⬜️🟩🟩⬛🟩\n🟩🟩🟩🟩🟩\n").unwrap();
+        assert_eq!(result.number, Some(String::from("1,234")));
+        assert!(result.hard);
+        assert_eq!(result.rows.len(), 3);
+        assert_eq!(result.rows[0].pattern.to_string(), ".y..g");
+        assert_eq!(result.rows[1].pattern.to_string(), ".gg.g");
+        assert!(result.is_solved());
+        assert!(result.board().is_err());
+
+        // high contrast squares and no header
+        let result = ShareGrid::parse("🟦⬛⬛⬛🟧").unwrap();
+        assert_eq!(result.number, None);
+        assert_eq!(result.rows[0].pattern.to_string(), "y...g");
+    }
+
+    #[test]
+    fn parse_all() {
+        let result = ShareGrid::parse_all("Wordle 12 2/6\n\n⬛🟨⬛⬛🟩\n🟩🟩🟩🟩🟩\n\nWordle 12 1/6\n\n🟩🟩🟩🟩🟩\n").unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].rows.len(), 2);
+        assert_eq!(result[1].rows.len(), 1);
+        assert_eq!(ShareGrid::parse_all("⬛🟨⬛⬛🟩").unwrap().len(), 1);
+        assert_eq!(ShareGrid::parse_all("Wordle 12 1/6\n\n🟩🟩🟩🟩🟩\nWordle 12 1/6\n🟩🟩🟩"), Err(String::from("line 5: 3 squares instead of 5")));
+        assert!(ShareGrid::parse_all("\n").is_err());
+    }
+
+    #[test]
+    fn parse_with_guesses() {
+        let result = ShareGrid::parse("crane ⬛⬛🟨⬛🟩\n🟩🟩🟩🟩🟩 SLATE").unwrap();
+        assert_eq!(result.rows[0].guess, Some(String::from("crane")));
+        assert_eq!(result.rows[1].guess, Some(String::from("slate")));
+        let board = result.board().unwrap();
+        assert_eq!(board.words[0].value(), "crane");
+        assert_eq!(board.words[0].letters[2].get_state(), LetterState::Present);
+        assert_eq!(board.words[2].letters[0].get_state(), LetterState::Disabled);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(ShareGrid::parse(""), Err(String::from("no rows of squares")));
+        assert_eq!(ShareGrid::parse("Wordle 12 4/6\n🟩🟩🟩🟩"), Err(String::from("line 2: 4 squares instead of 5")));
+        assert_eq!(ShareGrid::parse("Wordle twelve 4/6"), Err(String::from("line 1: 'twelve' isn't a puzzle number")));
+        assert_eq!(ShareGrid::parse("Wordle 12 4of6"), Err(String::from("line 1: '4of6' isn't a score like 4/6")));
+        assert_eq!(ShareGrid::parse("🟩🟩🟩🟩🟩 cat"), Err(String::from("line 1: the guess 'cat' needs 5 letters")));
+        assert_eq!(ShareGrid::parse("🟩🟩🟩🟩🟩 ❤"), Err(String::from("line 1: unexpected '❤'")));
+        assert!(ShareGrid::parse("🟩🟩🟩🟩🟩\nWordle 12 1/6").is_err());
+    }
+
+    #[test]
+    fn render() {
+        let mut board = Board::default();
+        board.words[0] = Word::feedback("crane", Pattern::new("crane", "slate"));
+        board.words[1] = Word::feedback("slate", Pattern::SOLVED);
+        let result = ShareGrid::from_board(&board, Some(String::from("1,234")));
+        assert_eq!(result.render(false), "Wordle 1,234 2/6\n\n⬛⬛🟩⬛🟩\n🟩🟩🟩🟩🟩\n");
+        assert_eq!(result.render(true), "Wordle 1,234 2/6\n\n⬛⬛🟧⬛🟧\n🟧🟧🟧🟧🟧\n");
+        // the rendered block reads back as the same grid, without the guesses
+        assert_eq!(ShareGrid::parse(&result.render(true)).unwrap().rows[0].pattern, result.rows[0].pattern);
+        assert_eq!(result.board().unwrap(), board);
+
+        board.words[1] = Word::feedback("plate", Pattern::new("plate", "slate"));
+        assert!(ShareGrid::from_board(&board, None).render(false).starts_with("Wordle X/6\n"));
+    }
+
+    #[test]
+    fn number() {
+        assert_eq!(ShareGrid::number_for(FIRST_PUZZLE_DAY), "0");
+        assert_eq!(ShareGrid::number_for(FIRST_PUZZLE_DAY + 1_234), "1,234");
+        assert_eq!(ShareGrid::number_for(FIRST_PUZZLE_DAY + 999), "999");
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt;
+use super::{Board, Pattern, Statistics, Word, MAX_ATTEMPTS};
+
+// how a strategy does when it plays a list of answers, one game each
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Simulation {
+    pub games: usize,
+    // how many games were solved in one guess, two guesses and so on
+    pub distribution: [usize; MAX_ATTEMPTS],
+    // the answers the strategy couldn't solve within the attempts allowed
+    pub failed: Vec<String>,
+}
+
+impl Simulation {
+    // play every answer with the statistics' strategy and settings, leaving its filters cleared
+    pub fn run(statistics: &mut Statistics, answers: &[String]) -> Simulation {
+        let mut simulation = Simulation::default();
+        // the first guess never depends on the answer so is only worked out once
+        statistics.filters.clear();
+        let opener = statistics.guess();
+        for answer in answers {
+            simulation.games += 1;
+            match Self::play(statistics, answer, &opener) {
+                Some(guesses) => simulation.distribution[guesses - 1] += 1,
+                None => simulation.failed.push(answer.clone()),
+            }
+        }
+        statistics.filters.clear();
+        simulation
+    }
+
+    // the number of guesses taken to solve the answer, if it was solved
+    pub fn play(statistics: &mut Statistics, answer: &str, opener: &str) -> Option<usize> {
+        let mut board = Board::default();
+        for row in 0..MAX_ATTEMPTS {
+            statistics.set_board(&board);
+            let guess = if row == 0 { opener.to_string() } else { statistics.guess() };
+            if guess.is_empty() {
+                return None;
+            }
+            let pattern = Pattern::new(&guess, answer);
+            board.words[row] = Word::feedback(&guess, pattern);
+            if pattern.is_solved() {
+                return Some(row + 1);
+            }
+        }
+        None
+    }
+
+    pub fn solved(&self) -> usize {
+        self.distribution.iter().sum()
+    }
+
+    // the average number of guesses over the games that were solved
+    pub fn average(&self) -> f64 {
+        let guesses: usize = self.distribution.iter().enumerate().map(|(idx, games)| (idx + 1) * games).sum();
+        guesses as f64 / self.solved().max(1) as f64
+    }
+}
+
+impl fmt::Display for Simulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Games: {}", self.games)?;
+        writeln!(f, "Solved: {}", self.solved())?;
+        writeln!(f, "Average guesses: {:.3}", self.average())?;
+        for (idx, games) in self.distribution.iter().enumerate() {
+            writeln!(f, "{}: {}", idx + 1, games)?;
+        }
+        if !self.failed.is_empty() {
+            writeln!(f, "Failed: {}", self.failed.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod simulation_tests {
+    use super::*;
+
+    fn statistics() -> Statistics {
+        let mut statistics = Statistics::default();
+        statistics.add_words("bills\nfills\nhills\nkills\nmills\nspeed\nabide");
+        statistics
+    }
+
+    #[test]
+    fn play() {
+        let mut statistics = statistics();
+        assert_eq!(Simulation::play(&mut statistics, "abide", "abide"), Some(1));
+        // repeated letters in the guess mustn't lose the answer
+        assert!(Simulation::play(&mut statistics, "abide", "speed").is_some());
+        assert_eq!(Simulation::play(&mut statistics, "crane", "speed"), None);
+    }
+
+    #[test]
+    fn run() {
+        let mut statistics = statistics();
+        let answers: Vec<String> = ["bills", "fills", "hills", "kills", "mills"].iter().map(|word| word.to_string()).collect();
+        let result = Simulation::run(&mut statistics, &answers);
+        assert_eq!(result.games, 5);
+        assert_eq!(result.solved() + result.failed.len(), 5);
+        assert!(result.average() >= 1.0);
+        assert!(statistics.filters.is_empty());
+        assert!(result.to_string().starts_with("Games: 5\n"));
+    }
+}
@@ -1,10 +1,35 @@
 use std::collections::hash_set::HashSet;
-use crate::wordle::letter_probability::LetterProbability;
-use super::{MAX_LETTERS, Word};
+use std::sync::Arc;
+use super::{Blocklist, Board, Combined, Commentary, Conflict, Correction, DecisionTree, Endgame, EndgameGoal, EndgameMove, DEFAULT_ENDGAME_THRESHOLD, GameAnalysis, GameState, Guarantee, Inference, LetterState, Lookahead, MatrixLookup, Minimax, MultiBoard, Narrowing, Opener, Openers, PackedWord, Pattern, MAX_ATTEMPTS, MAX_LETTERS, PAST_ANSWER_WEIGHT, PastAnswerMode, PastAnswers, PatternMatrix, Position, Priors, Progress, RejectReason, Reverse, ReverseRow, RowAnalysis, ShareGrid, StrategyKind, Word};
 
 #[derive(Debug, Clone)]
 pub struct Statistics {
     all_words: HashSet<String>,
+    // the words that can be the answer, every word when empty, the rest can only be guessed
+    answers: HashSet<String>,
+    // words that stay in the dictionary but are never guessed, kept when the dictionary is reloaded
+    blocklist: Blocklist,
+    past_answers: PastAnswers,
+    past_answer_mode: PastAnswerMode,
+    priors: Priors,
+    strategy: StrategyKind,
+    lookahead: Lookahead,
+    // the exact endgame search takes over from the strategy at this many candidates or fewer,
+    // off when zero
+    endgame_threshold: usize,
+    endgame_goal: EndgameGoal,
+    // guesses worked out ahead of time, used whenever the board follows one of its paths
+    tree: DecisionTree,
+    // the words always played first, whatever the strategy
+    opener: Opener,
+    // the best first guess worked out ahead of time, only used while it's for this dictionary
+    // and strategy
+    openers: Openers,
+    // the feedback for every guess and answer pair, worked out ahead of time
+    patterns: Option<Arc<PatternMatrix>>,
+    // the candidates after each row of the board, worked out again after anything else that
+    // changes the candidates
+    narrowing: Narrowing,
     pub filters: Vec<Word>,
 }
 
@@ -12,52 +37,359 @@ impl Default for Statistics {
     fn default() -> Self {
         Self {
             all_words: Default::default(),
-            filters: Default::default()
+            answers: Default::default(),
+            blocklist: Default::default(),
+            past_answers: Default::default(),
+            past_answer_mode: Default::default(),
+            priors: Default::default(),
+            strategy: Default::default(),
+            lookahead: Default::default(),
+            endgame_threshold: DEFAULT_ENDGAME_THRESHOLD,
+            endgame_goal: Default::default(),
+            tree: Default::default(),
+            opener: Default::default(),
+            openers: Default::default(),
+            patterns: None,
+            narrowing: Default::default(),
+            filters: Default::default(),
         }
     }
 }
 
 impl Statistics {
+    #[allow(clippy::needless_arbitrary_self_type)]
     pub fn len(self: &Self) -> usize {
         self.all_words.len()
     }
 
-    pub fn add_word(self: &mut Self, word: &String) {
+    // words are kept in lowercase, the same as the feedback and every list they are matched against
+    #[allow(clippy::needless_arbitrary_self_type)]
+    pub fn add_word(self: &mut Self, word: &str) {
         if word.len() > MAX_LETTERS {
             panic!("Word {} of length {} is greater than allowed length {}", word, word.len(), MAX_LETTERS)
         }
+        let word = word.to_lowercase();
+        if !Self::is_word(&word) {
+            panic!("Word {} has letters other than a to z", word)
+        }
+
+        self.all_words.insert(word);
+        self.narrowing.clear();
+    }
+
+    // add every word from a word per line list, ignoring blank lines and surrounding whitespace, a
+    // downloaded list can have lines that aren't words so they're skipped and counted
+    pub fn add_words(&mut self, content: &str) -> usize {
+        let mut skipped = 0;
+        for word in content.lines().map(|line| line.trim().to_lowercase()).filter(|line| !line.is_empty()) {
+            if Self::is_word(&word) {
+                self.add_word(&word);
+            } else {
+                skipped += 1;
+            }
+        }
+        skipped
+    }
+
+    fn is_word(word: &str) -> bool {
+        word.len() <= MAX_LETTERS && word.chars().all(|c| c.is_ascii_lowercase())
+    }
+
+    // use every row of the board that has been played as a filter
+    pub fn set_board(&mut self, board: &Board) {
+        self.filters = Self::board_filters(board);
+        if self.narrowing.is_empty() {
+            let candidates = self.all_words.iter()
+                .filter(|word| !self.excluded(&[], &self.blocklist, word))
+                .cloned()
+                .collect();
+            self.narrowing = Narrowing::new(candidates);
+        }
+        self.narrowing.apply(&self.filters);
+    }
 
-        self.all_words.insert(word.clone());
+    fn board_filters(board: &Board) -> Vec<Word> {
+        board.words.iter()
+            .filter(|word| !word.letters.iter().all(|letter| letter.get_state() == LetterState::Disabled))
+            .cloned()
+            .collect()
     }
 
+    #[allow(clippy::needless_arbitrary_self_type)]
     pub fn guess(self: &mut Self) -> String {
-        let mut probability = LetterProbability::default();
-        for word in self.all_words.iter() {
-            if  !self.filtered(word) {
-                probability.add_word(&word.clone());
+        self.guess_with(&Progress::default())
+    }
+
+    // the same as guess, which when cancelled gives back the best guess found so far
+    pub fn guess_with(&mut self, progress: &Progress) -> String {
+        if let Some(guess) = self.pinned_guess() {
+            return guess;
+        }
+        if let Some(guess) = self.tree.guess(&self.filters) {
+            if self.blocklist.get(guess).is_none() {
+                return guess.to_string();
+            }
+        }
+        if self.filters.is_empty() && self.openers.is_for(&self.openers_key()) {
+            if let Some(guess) = self.openers.best() {
+                return guess.to_string();
             }
         }
+        let position = self.position();
+        match self.endgame_for(&position, progress) {
+            Some(endgame) => endgame.guess,
+            None => self.strategy.strategy(&self.lookahead).guess_with(&position, progress).map(|guess| guess.to_string()).unwrap_or_default(),
+        }
+    }
 
-        let mut guessed_word : String = String::from("");
-        let mut guessed_word_score : f64 = 0.0f64;
+    // the next word of the pinned opener while the board has only been played with the opener, the
+    // second word is skipped when the first leaves two candidates or fewer
+    fn pinned_guess(&self) -> Option<String> {
+        let row = self.filters.len();
+        let words = self.opener.words();
+        let word = words.get(row)?;
+        let followed = self.filters.iter().zip(words.iter()).all(|(filter, word)| filter.value() == *word);
+        if !followed || !self.all_words.contains(word) || self.blocklist.get(word).is_some() || (row > 0 && self.candidate_count() <= 2) {
+            return None;
+        }
+        Some(word.clone())
+    }
 
+    // the key the opener ranking for this dictionary and strategy is saved under
+    pub fn openers_key(&self) -> String {
+        Openers::key(&self.position_with(&[]), self.strategy)
+    }
 
-        for word in self.all_words.iter() {
-            if self.filtered(word) {
-                continue;
+    // rank every first guess with the strategy
+    pub fn analyse_openers(&self) -> Openers {
+        self.analyse_openers_with(&Progress::default()).unwrap_or_default()
+    }
+
+    pub fn analyse_openers_with(&self, progress: &Progress) -> Option<Openers> {
+        let position = self.position_with(&[]);
+        Openers::analyse_with(&position, self.strategy.strategy(&self.lookahead).as_ref(), &Openers::key(&position, self.strategy), progress)
+    }
+
+    // the best move from an exact search, when few enough candidates are left for it to take over
+    pub fn endgame_with(&self, progress: &Progress) -> Option<EndgameMove> {
+        self.endgame_for(&self.position(), progress)
+    }
+
+    fn endgame_for(&self, position: &Position, progress: &Progress) -> Option<EndgameMove> {
+        if position.candidates.len() > self.endgame_threshold {
+            return None;
+        }
+        Endgame { goal: self.endgame_goal }.solve_with(position, progress)
+    }
+
+    // the candidates, their weights and the allowed guesses for the strategy to choose from
+    pub fn position(&self) -> Position {
+        self.position_with(&self.filters)
+    }
+
+    fn position_with(&self, filters: &[Word]) -> Position {
+        // the narrowing usually has the candidates already, so they're only copied when it doesn't
+        let collected;
+        let words = match self.narrowing.candidates(filters) {
+            Some(candidates) => candidates,
+            None => {
+                collected = self.candidates_with(filters);
+                collected.as_slice()
             }
-            let score = probability.score_word(&word.clone());
-            if score > guessed_word_score {
-                guessed_word_score = score;
-                guessed_word = word.clone();
+        };
+        let candidates: Vec<PackedWord> = words.iter().map(|word| Self::pack(word)).collect();
+        let mut guesses: Vec<PackedWord> = self.all_words.iter()
+            .filter(|word| self.blocklist.get(word).is_none())
+            .map(|word| Self::pack(word))
+            .collect();
+        guesses.sort();
+        Position {
+            weights: words.iter().map(|word| self.weight(word)).collect(),
+            guessable: words.iter().map(|word| self.blocklist.get(word).is_none()).collect(),
+            lookup: self.patterns.as_ref().and_then(|patterns| MatrixLookup::new(patterns, &candidates)),
+            letters: self.narrowing.letters(filters).cloned(),
+            candidates,
+            guesses,
+            attempts_left: MAX_ATTEMPTS.saturating_sub(filters.len()),
+        }
+    }
+
+    // add_word only lets in words of a to z
+    fn pack(word: &str) -> PackedWord {
+        PackedWord::new(word).expect("dictionary words are lowercase a to z")
+    }
+
+    // the position for one of several boards played at once, which share their attempts
+    pub fn board_position(&self, board: &Board, attempts_left: usize) -> Position {
+        Position { attempts_left, ..self.position_with(&Self::board_filters(board)) }
+    }
+
+    pub fn board_candidate_count(&self, board: &Board) -> usize {
+        self.count_with(&Self::board_filters(board))
+    }
+
+    // the next guess to play on every unsolved board, before anything has been played every
+    // board is the same so the first guess is the same as for one board
+    pub fn multi_guess_with(&mut self, game: &MultiBoard, progress: &Progress) -> String {
+        if game.guess_num == 0 {
+            self.set_board(&Board::default());
+            return self.guess_with(progress);
+        }
+        let positions: Vec<Position> = game.unsolved().map(|board| self.board_position(board, game.attempts_left())).collect();
+        Combined::guess_with(&positions, progress).map(|guess| guess.to_string()).unwrap_or_default()
+    }
+
+    // how the solver would have played a row of the board, from the rows before it
+    pub fn commentary_with(&mut self, board: &Board, row: usize, progress: &Progress) -> Commentary {
+        let position = self.row_position(board, row);
+        self.commentary_for(&position, board, row, progress)
+    }
+
+    // every row played on the board graded against the solver
+    pub fn analyse_with(&mut self, board: &Board, progress: &Progress) -> GameAnalysis {
+        let rows = Self::board_filters(board).len();
+        let mut analysis = GameAnalysis::default();
+        for row in 0..rows {
+            if progress.is_cancelled() {
+                break;
             }
+            let position = self.row_position(board, row);
+            let commentary = self.commentary_for(&position, board, row, progress);
+            analysis.rows.push(RowAnalysis::new(commentary, &position));
         }
+        analysis
+    }
+
+    // the answers that fit everyone's share grids, most likely first, before any feedback
+    pub fn infer_with(&self, grids: &[ShareGrid], progress: &Progress) -> Vec<(f64, String)> {
+        Inference::rank_with(&self.position_with(&[]), grids, progress)
+    }
+
+    // the words that could have been played in each row of a grid with a known answer
+    pub fn reverse(&self, answer: &str, grid: &ShareGrid) -> Vec<ReverseRow> {
+        let mut guesses: Vec<String> = self.guesses().into_iter().collect();
+        guesses.sort();
+        let patterns: Vec<Pattern> = grid.rows.iter().map(|row| row.pattern).collect();
+        Reverse::solve(answer, &patterns, &guesses)
+    }
 
-        guessed_word
+    // the position before the row was played
+    fn row_position(&mut self, board: &Board, row: usize) -> Position {
+        let mut before = board.clone();
+        for word in before.words.iter_mut().skip(row) {
+            *word = Word::default();
+        }
+        self.set_board(&before);
+        self.position()
     }
 
-    fn filtered(self: &Self, word: &String) -> bool {
-        for filter in self.filters.iter() {
+    fn commentary_for(&mut self, position: &Position, board: &Board, row: usize, progress: &Progress) -> Commentary {
+        let solver_guess = self.guess_with(progress);
+        let guess = board.words[row].value();
+        let expected_bits = |guess: &str| PackedWord::new(guess).map_or(0.0, |guess| Commentary::expected_bits(position, &guess));
+        let after: Vec<usize> = position.candidates.iter().enumerate()
+            .filter(|(_, word)| !board.words[row].filter(&word.to_string()))
+            .map(|(idx, _)| idx)
+            .collect();
+        Commentary {
+            before: position.candidates.len(),
+            after: after.len(),
+            before_weight: position.total_weight(),
+            after_weight: after.iter().map(|idx| position.weights[*idx]).sum(),
+            expected_bits: expected_bits(&guess),
+            solver_expected_bits: expected_bits(&solver_guess),
+            solver_guess,
+            guess,
+        }
+    }
+
+    // whether every remaining candidate can be solved in the attempts left
+    pub fn guarantee(&self) -> Guarantee {
+        Minimax::guarantee(&self.position())
+    }
+
+    // how likely the word is to be the answer relative to the other words
+    pub fn weight(&self, word: &str) -> f64 {
+        let mut weight = self.priors.weight(word);
+        if self.past_answer_mode == PastAnswerMode::DownWeight && self.past_answers.contains(word) {
+            weight *= PAST_ANSWER_WEIGHT;
+        }
+        weight
+    }
+
+    // every word that is still consistent with the filters, in alphabetical order
+    pub fn candidates(&self) -> Vec<String> {
+        self.candidates_with(&self.filters)
+    }
+
+    fn candidates_with(&self, filters: &[Word]) -> Vec<String> {
+        if let Some(candidates) = self.narrowing.candidates(filters) {
+            return candidates.to_vec();
+        }
+        let mut candidates: Vec<String> = self.all_words.iter()
+            .filter(|word| !self.excluded(filters, &self.blocklist, word))
+            .cloned()
+            .collect();
+        candidates.sort();
+        candidates
+    }
+
+    pub fn candidate_count(&self) -> usize {
+        self.count_with(&self.filters)
+    }
+
+    fn count_with(&self, filters: &[Word]) -> usize {
+        if let Some(candidates) = self.narrowing.candidates(filters) {
+            return candidates.len();
+        }
+        self.all_words.iter().filter(|word| !self.excluded(filters, &self.blocklist, word)).count()
+    }
+
+    // the candidates for a game state from the history rather than the current filters
+    pub fn candidates_for(&self, state: &GameState) -> Vec<String> {
+        let filters = Self::board_filters(&state.board);
+        let mut candidates: Vec<String> = self.all_words.iter()
+            .filter(|word| !self.excluded(&filters, &state.blocklist, word))
+            .cloned()
+            .collect();
+        candidates.sort();
+        candidates
+    }
+
+    // contradictions between the filters, each filter is one row of the board
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        Conflict::find(&self.filters)
+    }
+
+    // the single tile change that leaves the most candidates, favouring tiles involved in a conflict
+    pub fn suggest_correction(&self) -> Option<Correction> {
+        let mut best: Option<Correction> = None;
+        for (tile, state) in Conflict::corrections(&self.filters, &self.conflicts()) {
+            let mut corrected = self.filters.clone();
+            corrected[tile.row].letters[tile.col].set_state(state.clone());
+            if !Conflict::find(&corrected).is_empty() {
+                continue;
+            }
+            let candidates = self.count_with(&corrected);
+            if candidates > 0 && best.as_ref().map_or(true, |best| candidates > best.candidates) {
+                best = Some(Correction { tile, state, candidates });
+            }
+        }
+        best
+    }
+
+    // unwanted words are still possible answers so only words the game doesn't accept are excluded
+    fn excluded(&self, filters: &[Word], blocklist: &Blocklist, word: &str) -> bool {
+        if !self.answers.is_empty() && !self.answers.contains(word) {
+            return true;
+        }
+        if blocklist.get(word) == Some(RejectReason::NotAccepted) {
+            return true;
+        }
+        if self.past_answer_mode == PastAnswerMode::Exclude && self.past_answers.contains(word) {
+            return true;
+        }
+        for filter in filters.iter() {
             if filter.filter(word) {
                 return true;
             }
@@ -65,12 +397,148 @@ impl Statistics {
         false
     }
 
+    #[allow(clippy::needless_arbitrary_self_type)]
     pub fn clear(self: &mut Self) {
         self.all_words.clear();
+        self.narrowing.clear();
+    }
+
+    pub fn remove_word(&mut self, word: &str, reason: RejectReason) {
+        self.blocklist.insert(word, reason);
+        if reason == RejectReason::NotAccepted {
+            self.narrowing.remove(word);
+        }
+    }
+
+    pub fn restore_word(&mut self, word: &str) {
+        self.blocklist.remove(word);
+        self.narrowing.clear();
+    }
+
+    pub fn blocklist(&self) -> &Blocklist {
+        &self.blocklist
+    }
+
+    pub fn set_blocklist(&mut self, blocklist: Blocklist) {
+        // undo puts back the same blocklist every time, which leaves the candidates as they were
+        if blocklist != self.blocklist {
+            self.blocklist = blocklist;
+            self.narrowing.clear();
+        }
+    }
+
+    pub fn past_answers(&self) -> &PastAnswers {
+        &self.past_answers
+    }
+
+    pub fn set_past_answers(&mut self, past_answers: PastAnswers) {
+        self.past_answers = past_answers;
+        self.narrowing.clear();
+    }
+
+    pub fn priors(&self) -> &Priors {
+        &self.priors
+    }
+
+    pub fn set_priors(&mut self, priors: Priors) {
+        self.priors = priors;
+    }
+
+    pub fn strategy(&self) -> StrategyKind {
+        self.strategy
+    }
+
+    pub fn set_strategy(&mut self, strategy: StrategyKind) {
+        self.strategy = strategy;
+    }
+
+    // only words in the dictionary can be answers, whatever the list says
+    pub fn set_answers(&mut self, answers: &[String]) {
+        self.answers = answers.iter().cloned().collect();
+        self.narrowing.clear();
+    }
+
+    // every word that may be guessed
+    pub fn guesses(&self) -> HashSet<String> {
+        self.all_words.iter().filter(|word| self.blocklist.get(word).is_none()).cloned().collect()
+    }
+
+    // map the saved pattern matrix for the dictionary, building it when it hasn't been saved yet,
+    // it's used even when it couldn't be saved
+    pub fn load_patterns(&mut self) -> std::io::Result<()> {
+        let (guesses, answers) = self.pattern_words();
+        let (patterns, saved) = PatternMatrix::open(&guesses, &answers);
+        self.set_patterns(patterns);
+        saved
+    }
+
+    // the guesses and answers the pattern matrix for the dictionary is laid out by, in order
+    pub fn pattern_words(&self) -> (Vec<String>, Vec<String>) {
+        let mut guesses: Vec<String> = self.all_words.iter().cloned().collect();
+        guesses.sort();
+        let answers: Vec<String> = if self.answers.is_empty() {
+            guesses.clone()
+        } else {
+            guesses.iter().filter(|word| self.answers.contains(*word)).cloned().collect()
+        };
+        (guesses, answers)
+    }
+
+    pub fn set_patterns(&mut self, patterns: PatternMatrix) {
+        self.patterns = Some(Arc::new(patterns));
+    }
+
+    // the opener's words that can't be guessed with this dictionary, they're never played
+    pub fn set_opener(&mut self, opener: Opener) -> Vec<String> {
+        let guesses = self.guesses();
+        let unknown = opener.words().iter().filter(|word| !guesses.contains(*word)).cloned().collect();
+        self.opener = opener;
+        unknown
+    }
+
+    pub fn set_openers(&mut self, openers: Openers) {
+        self.openers = openers;
+    }
+
+    pub fn tree(&self) -> &DecisionTree {
+        &self.tree
+    }
+
+    pub fn set_tree(&mut self, tree: DecisionTree) {
+        self.tree = tree;
     }
 
-    pub fn remove_word(self: &mut Self, word: &String) {
-        self.all_words.remove(word);
+    pub fn lookahead(&self) -> Lookahead {
+        self.lookahead
+    }
+
+    pub fn set_lookahead(&mut self, lookahead: Lookahead) {
+        self.lookahead = lookahead;
+    }
+
+    pub fn endgame_threshold(&self) -> usize {
+        self.endgame_threshold
+    }
+
+    pub fn set_endgame_threshold(&mut self, threshold: usize) {
+        self.endgame_threshold = threshold;
+    }
+
+    pub fn endgame_goal(&self) -> EndgameGoal {
+        self.endgame_goal
+    }
+
+    pub fn set_endgame_goal(&mut self, goal: EndgameGoal) {
+        self.endgame_goal = goal;
+    }
+
+    pub fn past_answer_mode(&self) -> PastAnswerMode {
+        self.past_answer_mode
+    }
+
+    pub fn set_past_answer_mode(&mut self, mode: PastAnswerMode) {
+        self.past_answer_mode = mode;
+        self.narrowing.clear();
     }
 }
 
@@ -78,22 +546,30 @@ impl Statistics {
 
 #[cfg(test)]
 mod statistics_tests {
-    use crate::wordle::{LetterState};
+    use crate::wordle::{test_word, LetterState, Pattern, Tile, DEFAULT_ENDGAME_THRESHOLD};
     use super::*;
 
+    fn packed(words: &[&str]) -> Vec<PackedWord> {
+        words.iter().map(|word| test_word(word)).collect()
+    }
+
     #[test]
     fn default() {
         let result: Statistics = Default::default();
         assert_eq!(result.all_words.len(), 0);
         assert_eq!(result.filters.len(), 0);
+        assert_eq!(result.endgame_threshold(), DEFAULT_ENDGAME_THRESHOLD);
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn add_word() {
         let mut result: Statistics = Default::default();
         result.add_word(&String::from("test"));
         assert_eq!(result.all_words.len(), 1);
         assert_eq!(result.all_words.contains(&String::from("test")), true);
+        result.add_word("CRANE");
+        assert!(result.all_words.contains("crane"));
     }
 
     fn default_word() -> Word {
@@ -138,6 +614,338 @@ mod statistics_tests {
         assert_eq!(result.guess(), "");
     }
 
+    #[test]
+    fn candidates() {
+        let mut result: Statistics = Default::default();
+        result.add_word(&String::from("abcde"));
+        result.add_word(&String::from("edbca"));
+        result.add_word(&String::from("bcdea"));
+        assert_eq!(result.candidates(), vec!["abcde", "bcdea", "edbca"]);
+
+        result.filters.push(default_word().clone());
+        assert_eq!(result.candidates(), vec!["bcdea", "edbca"]);
+    }
+
+    fn row(value: &str, present: usize) -> Word {
+        let mut word: Word = Default::default();
+        word.set(value);
+        word.letters[present].set_state(LetterState::Present);
+        word
+    }
+
+    #[test]
+    fn suggest_correction() {
+        let mut result: Statistics = Default::default();
+        result.add_word(&String::from("abcde"));
+        result.add_word(&String::from("fghij"));
+
+        // 'a' is yellow in the first row and absent in the second
+        result.filters.push(row("zaxyq", 1));
+        result.filters.push(row("wvuta", 0));
+        result.filters[1].letters[0].set_state(LetterState::Incorrect);
+        assert_eq!(result.candidate_count(), 0);
+        assert_eq!(result.conflicts().len(), 1);
+
+        let correction = result.suggest_correction().unwrap();
+        assert_eq!(correction.tile, Tile { row: 0, col: 1 });
+        assert_eq!(correction.state, LetterState::Incorrect);
+        assert_eq!(correction.candidates, 1);
+    }
+
+    #[test]
+    fn remove_word() {
+        let mut result: Statistics = Default::default();
+        result.add_word(&String::from("abcde"));
+        result.add_word(&String::from("edbca"));
+        result.remove_word("abcde", RejectReason::NotAccepted);
+        assert_eq!(result.guess(), "edbca");
+        assert_eq!(result.candidates(), vec!["edbca"]);
+        assert_eq!(result.blocklist().get("abcde"), Some(RejectReason::NotAccepted));
+
+        result.restore_word("abcde");
+        assert_eq!(result.candidates(), vec!["abcde", "edbca"]);
+
+        // unwanted words are never guessed but are still candidates
+        result.remove_word("edbca", RejectReason::Unwanted);
+        assert_eq!(result.guess(), "abcde");
+        assert_eq!(result.candidates(), vec!["abcde", "edbca"]);
+
+        // the blocklist outlives the dictionary
+        result.clear();
+        assert_eq!(result.blocklist().len(), 1);
+    }
+
+    #[test]
+    fn add_words() {
+        let mut result: Statistics = Default::default();
+        result.add_words("abcde\r\n\n  edbca \n");
+        assert_eq!(result.candidates(), vec!["abcde", "edbca"]);
+
+        // anything that isn't a word of a to z is left out rather than stopping the list
+        assert_eq!(result.add_words("don't\ncr4ne\ncafé\ntesttest\nCRANE\n"), 4);
+        assert_eq!(result.candidates(), vec!["abcde", "crane", "edbca"]);
+    }
+
+    #[test]
+    fn past_answers() {
+        let mut result: Statistics = Default::default();
+        result.add_word(&String::from("abcde"));
+        result.add_word(&String::from("abcdf"));
+        result.add_word(&String::from("fghie"));
+        result.set_past_answers(PastAnswers::parse("abcde"));
+        assert_eq!(result.guess(), "abcde");
+
+        result.set_past_answer_mode(PastAnswerMode::DownWeight);
+        assert_eq!(result.guess(), "abcdf");
+        assert_eq!(result.candidate_count(), 3);
+
+        result.set_past_answer_mode(PastAnswerMode::Exclude);
+        assert_ne!(result.guess(), "abcde");
+        assert_eq!(result.candidates(), vec!["abcdf", "fghie"]);
+    }
+
+    #[test]
+    fn priors() {
+        let mut result: Statistics = Default::default();
+        result.add_word(&String::from("abcde"));
+        result.add_word(&String::from("abcdf"));
+        result.add_word(&String::from("fghie"));
+        result.set_strategy(StrategyKind::MostLikely);
+        result.set_priors(Priors::parse("fghie\t100\nabcde\t10\n"));
+        assert_eq!(result.guess(), "fghie");
+        assert_eq!(result.weight("abcdf"), 10.0);
+
+        result.set_past_answers(PastAnswers::parse("fghie"));
+        result.set_past_answer_mode(PastAnswerMode::DownWeight);
+        assert_eq!(result.weight("fghie"), 100.0 * PAST_ANSWER_WEIGHT);
+        assert_eq!(result.guess(), "abcde");
+    }
+
+    #[test]
+    fn guarantee() {
+        let mut result: Statistics = Default::default();
+        result.add_word(&String::from("abcde"));
+        result.add_word(&String::from("edbca"));
+        assert_eq!(result.guarantee(), Guarantee::Guaranteed(2));
+        // one attempt left and two words it could be
+        let mut filter: Word = Default::default();
+        filter.set("xxxxx");
+        result.filters = vec![filter; MAX_ATTEMPTS - 1];
+        assert_eq!(result.guarantee(), Guarantee::Impossible);
+    }
+
+    #[test]
+    fn endgame() {
+        let mut result: Statistics = Default::default();
+        for word in ["bills", "fills", "hills", "kills", "mills", "fbhkm"] {
+            result.add_word(word);
+        }
+        let mut filter: Word = Default::default();
+        filter.set("xxlls");
+        for (idx, state) in [LetterState::Incorrect, LetterState::Incorrect, LetterState::Correct, LetterState::Correct, LetterState::Correct].into_iter().enumerate() {
+            filter.letters[idx].set_state(state);
+        }
+        result.filters.push(filter);
+        result.set_strategy(StrategyKind::MostLikely);
+        result.set_endgame_threshold(0);
+        assert_eq!(result.endgame_with(&Progress::default()), None);
+        assert_eq!(result.guess(), "bills");
+
+        // only worth playing a word that can't be the answer when the search sees every guess
+        result.set_endgame_threshold(DEFAULT_ENDGAME_THRESHOLD);
+        assert_eq!(result.guess(), "fbhkm");
+        assert_eq!(result.endgame_with(&Progress::default()).unwrap().outcome.solve_probability, 1.0);
+    }
+
+    #[test]
+    fn endgame_cancelled() {
+        let mut result: Statistics = Default::default();
+        for word in ["bills", "fills", "hills", "kills", "mills", "fbhkm"] {
+            result.add_word(word);
+        }
+        result.filters.push(Word::feedback("tolls", Pattern::new("tolls", "hills")));
+        result.set_priors(Priors::parse("hills\t100\nbills\t1\n"));
+        result.set_strategy(StrategyKind::Minimax);
+
+        // the endgame tries the likeliest answer first and gives it back once it's worked out,
+        // rather than leaving the cancelled strategy to play the first word it looks at
+        let progress = Progress::default();
+        progress.cancel();
+        assert_eq!(result.guess_with(&progress), "hills");
+        result.set_endgame_threshold(0);
+        assert_eq!(result.guess_with(&progress), "bills");
+    }
+
+    #[test]
+    fn answers() {
+        let mut result: Statistics = Default::default();
+        result.add_words("abcde\nedbca\nfghij");
+        result.set_answers(&[String::from("edbca"), String::from("fghij"), String::from("zzzzz")]);
+        assert_eq!(result.candidates(), vec!["edbca", "fghij"]);
+        assert_eq!(result.position().guesses, packed(&["abcde", "edbca", "fghij"]));
+    }
+
+    #[test]
+    fn tree() {
+        let mut result: Statistics = Default::default();
+        result.add_words("crane\nslate\nplate\nfuzzy");
+        result.set_tree(DecisionTree::parse("fuzzy:..... slate:ggggg").unwrap());
+        assert_eq!(result.guess(), "fuzzy");
+        result.filters.push(Word::feedback("fuzzy", Pattern::new("fuzzy", "slate")));
+        assert_eq!(result.guess(), "slate");
+
+        // off the tree the strategy takes over
+        result.filters[0] = Word::feedback("crane", Pattern::new("crane", "slate"));
+        assert_ne!(result.guess(), "");
+        result.filters.clear();
+        result.remove_word("fuzzy", RejectReason::NotAccepted);
+        assert_ne!(result.guess(), "fuzzy");
+    }
+
+    #[test]
+    fn opener() {
+        let mut result: Statistics = Default::default();
+        result.add_words("crane\nslate\nplate\nfuzzy\nbills");
+        assert!(result.set_opener(Opener::parse("fuzzy bills").unwrap()).is_empty());
+        assert_eq!(result.guess(), "fuzzy");
+        result.filters.push(Word::feedback("fuzzy", Pattern::new("fuzzy", "slate")));
+        assert_eq!(result.guess(), "bills");
+        result.filters.push(Word::feedback("bills", Pattern::new("bills", "slate")));
+        assert_ne!(result.guess(), "bills");
+
+        // off the opener, or with too few candidates left for the second word to be worth playing
+        result.filters = vec![Word::feedback("crane", Pattern::new("crane", "slate"))];
+        assert_ne!(result.guess(), "bills");
+        result.filters = vec![Word::feedback("fuzzy", Pattern::new("fuzzy", "fuzzy"))];
+        assert_ne!(result.guess(), "bills");
+
+        // words missing from the dictionary are reported and never played
+        result.filters.clear();
+        assert_eq!(result.set_opener(Opener::parse("zzzzz").unwrap()), ["zzzzz"]);
+        assert_ne!(result.guess(), "zzzzz");
+    }
+
+    #[test]
+    fn openers() {
+        let mut result: Statistics = Default::default();
+        result.add_words("bills\nfills\nhills\nkills\nmills\nfbhkm");
+        result.set_strategy(StrategyKind::Minimax);
+        let openers = result.analyse_openers();
+        assert!(openers.is_for(&result.openers_key()));
+        assert_eq!(openers.best(), Some("fbhkm"));
+
+        // a ranking for another strategy isn't used
+        result.set_openers(Openers::parse(&openers.to_string().replace("fbhkm", "mills")));
+        assert_eq!(result.guess(), "mills");
+        result.set_strategy(StrategyKind::MostLikely);
+        assert_ne!(result.guess(), "mills");
+    }
+
+    #[test]
+    fn position() {
+        let mut result: Statistics = Default::default();
+        result.add_word(&String::from("abcde"));
+        result.add_word(&String::from("edbca"));
+        result.add_word(&String::from("fghij"));
+        result.remove_word("fghij", RejectReason::NotAccepted);
+        result.remove_word("edbca", RejectReason::Unwanted);
+        result.filters.push(Default::default());
+        result.filters[0].set("xxxxx");
+
+        let position = result.position();
+        assert_eq!(position.candidates, packed(&["abcde", "edbca"]));
+        assert_eq!(position.guessable, vec![true, false]);
+        assert_eq!(position.guesses, packed(&["abcde"]));
+        assert_eq!(position.weights, vec![1.0, 1.0]);
+        assert_eq!(position.attempts_left, MAX_ATTEMPTS - 1);
+    }
+
+    #[test]
+    fn candidates_for() {
+        let mut result: Statistics = Default::default();
+        result.add_word(&String::from("abcde"));
+        result.add_word(&String::from("edbca"));
+        result.add_word(&String::from("fghij"));
+
+        let mut state: GameState = Default::default();
+        state.board.words[0] = default_word();
+        state.blocklist.insert("fghij", RejectReason::NotAccepted);
+        assert_eq!(result.candidates_for(&state), vec!["edbca"]);
+
+        // the current filters are left alone
+        assert_eq!(result.candidates().len(), 3);
+    }
+
+    #[test]
+    fn set_board() {
+        let mut result: Statistics = Default::default();
+        let mut board: Board = Default::default();
+        board.words[0] = default_word();
+        result.set_board(&board);
+        assert_eq!(result.filters.len(), 1);
+    }
+
+    #[test]
+    fn multi_guess() {
+        let mut result: Statistics = Default::default();
+        result.add_words("crane\nslate\nplate\nabout\nfuzzy");
+        let mut game = MultiBoard::new(2);
+        let first = result.multi_guess_with(&game, &Progress::default());
+        assert!(!first.is_empty());
+
+        // the second board is down to one candidate so it gets solved next
+        game.play("crane");
+        game.boards[0].words[0] = Word::feedback("crane", Pattern::new("crane", "slate"));
+        game.boards[1].words[0] = Word::feedback("crane", Pattern::new("crane", "fuzzy"));
+        assert_eq!(result.board_candidate_count(&game.boards[0]), 2);
+        assert_eq!(result.board_position(&game.boards[1], game.attempts_left()).attempts_left, 6);
+        assert_eq!(result.multi_guess_with(&game, &Progress::default()), "fuzzy");
+    }
+
+    #[test]
+    fn commentary() {
+        let mut result: Statistics = Default::default();
+        result.add_words("bills\nfills\nhills\nmills\nfbhmz");
+        result.set_answers(&["bills", "fills", "hills", "mills"].map(String::from));
+        result.set_strategy(StrategyKind::MinExpectedGuesses);
+        let mut board: Board = Default::default();
+        board.words[0] = Word::feedback("bills", Pattern::new("bills", "hills"));
+        let commentary = result.commentary_with(&board, 0, &Progress::default());
+        assert_eq!((commentary.before, commentary.after), (4, 3));
+        assert_eq!((commentary.before_weight, commentary.after_weight), (4.0, 3.0));
+        assert_eq!(commentary.solver_guess, "fbhmz");
+        assert_eq!(commentary.solver_expected_bits, 2.0);
+        assert_eq!(result.filters.len(), 0);
+
+        board.words[1] = Word::feedback("hills", Pattern::SOLVED);
+        let analysis = result.analyse_with(&board, &Progress::default());
+        assert_eq!(analysis.rows.len(), 2);
+        assert_eq!(analysis.rows[0].commentary, commentary);
+        assert_eq!((analysis.rows[1].commentary.before, analysis.rows[1].commentary.after), (3, 1));
+        assert!(analysis.rows[1].skill() > 99.99);
+    }
+
+    #[test]
+    fn narrowing() {
+        let mut result: Statistics = Default::default();
+        result.add_words("crane\nslate\nplate\nabout");
+        let mut board: Board = Default::default();
+        board.words[0] = Word::feedback("crane", Pattern::new("crane", "slate"));
+        result.set_board(&board);
+        assert_eq!(result.candidates(), vec!["plate", "slate"]);
+        assert_eq!(result.position().letters.map(|letters| letters.score_word(&packed(&["slate"])[0])), Some(4.5));
+
+        // rejecting a word takes it out without starting again
+        result.remove_word("plate", RejectReason::NotAccepted);
+        assert_eq!(result.candidates(), vec!["slate"]);
+
+        // undoing the row goes back to every candidate
+        result.set_board(&Board::default());
+        assert_eq!(result.candidates(), vec!["about", "crane", "slate"]);
+        result.restore_word("plate");
+        assert_eq!(result.candidate_count(), 4);
+    }
+
     #[test]
     fn clear() {
         let mut result: Statistics = Default::default();
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+// where the solver keeps files between sessions, APPDATA on windows and a dot directory in HOME everywhere else
+pub fn data_dir() -> PathBuf {
+    if let Some(app_data) = std::env::var_os("APPDATA") {
+        return PathBuf::from(app_data).join("wordle_solve");
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        return PathBuf::from(home).join(".wordle_solve");
+    }
+    PathBuf::from(".wordle_solve")
+}
+
+pub fn data_file(name: &str) -> PathBuf {
+    data_dir().join(name)
+}
+
+// write the contents to the path, creating any missing directories first
+pub fn write_file(path: &PathBuf, contents: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)
+}
+
+// a hash of the lines that stays the same between runs and builds, for naming cached files
+pub fn fingerprint<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> u64 {
+    // 64 bit FNV-1a, with a newline after every line
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for line in lines {
+        for byte in line.as_ref().bytes().chain(std::iter::once(b'\n')) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod storage_tests {
+    use super::*;
+
+    #[test]
+    fn data_file() {
+        let result = super::data_file("blocklist.txt");
+        assert!(result.starts_with(data_dir()));
+        assert!(result.ends_with("blocklist.txt"));
+    }
+
+    #[test]
+    fn write_file() {
+        let path = std::env::temp_dir().join("wordle_solve_storage_tests").join("nested").join("file.txt");
+        super::write_file(&path, "crane").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "crane");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+    }
+
+    #[test]
+    fn fingerprint() {
+        assert_eq!(super::fingerprint(["crane", "slate"]), super::fingerprint(vec![String::from("crane"), String::from("slate")]));
+        assert_ne!(super::fingerprint(["crane", "slate"]), super::fingerprint(["cranes", "late"]));
+        assert_ne!(super::fingerprint(["crane"]), super::fingerprint(Vec::<String>::new()));
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use crate::wordle::letter_probability::LetterProbability;
+use super::{Lookahead, MatrixLookup, Minimax, PackedWord, Pattern, Progress, ALPHABET, MAX_ATTEMPTS};
+
+// roughly how many guess and answer pairs a strategy may compare before it narrows its guesses
+pub const GUESS_BUDGET: usize = 2_000_000;
+
+// everything a strategy needs to know to pick the next guess, the words are packed so comparing
+// them never looks at a string
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    // the words that could still be the answer, in alphabetical order, with how likely each is
+    pub candidates: Vec<PackedWord>,
+    pub weights: Vec<f64>,
+    // unwanted words can still be the answer but are never suggested
+    pub guessable: Vec<bool>,
+    // every word that may be suggested, including ones that can't be the answer
+    pub guesses: Vec<PackedWord>,
+    pub attempts_left: usize,
+    // the precomputed feedback for the candidates, worked out as needed without it
+    pub lookup: Option<MatrixLookup>,
+    // how often each letter is in each position among the candidates, when already counted
+    pub letters: Option<LetterProbability>,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self {
+            candidates: Vec::new(),
+            weights: Vec::new(),
+            guessable: Vec::new(),
+            guesses: Vec::new(),
+            attempts_left: MAX_ATTEMPTS,
+            lookup: None,
+            letters: None,
+        }
+    }
+}
+
+impl Position {
+    // every word equally likely, guessable and a candidate
+    #[cfg(test)]
+    pub fn uniform(words: &[&str]) -> Position {
+        let words: Vec<PackedWord> = words.iter().map(|word| super::test_word(word)).collect();
+        Position {
+            candidates: words.clone(),
+            weights: vec![1.0; words.len()],
+            guessable: vec![true; words.len()],
+            guesses: words,
+            ..Default::default()
+        }
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.weights.iter().sum()
+    }
+
+    // the same position with only the given candidates left, after one more guess
+    pub fn narrow(&self, candidates: &[usize]) -> Position {
+        Position {
+            candidates: candidates.iter().map(|idx| self.candidates[*idx]).collect(),
+            weights: candidates.iter().map(|idx| self.weights[*idx]).collect(),
+            guessable: candidates.iter().map(|idx| self.guessable[*idx]).collect(),
+            guesses: self.guesses.clone(),
+            attempts_left: self.attempts_left.saturating_sub(1),
+            lookup: self.lookup.as_ref().map(|lookup| lookup.narrow(candidates)),
+            letters: None,
+        }
+    }
+
+    // the feedback the guess would get against each candidate
+    pub fn patterns(&self, guess: &PackedWord) -> Vec<Pattern> {
+        match self.lookup.as_ref().and_then(|lookup| lookup.patterns(guess)) {
+            Some(patterns) => patterns,
+            None => self.candidates.iter().map(|candidate| Pattern::packed(guess, candidate)).collect(),
+        }
+    }
+
+    // split the candidates by the feedback the guess would get, each bucket holds candidate indexes
+    pub fn partition(&self, guess: &PackedWord) -> HashMap<Pattern, Vec<usize>> {
+        let mut buckets: HashMap<Pattern, Vec<usize>> = HashMap::new();
+        for (idx, pattern) in self.patterns(guess).into_iter().enumerate() {
+            buckets.entry(pattern).or_default().push(idx);
+        }
+        buckets
+    }
+
+    // the letter counts for the candidates, counting them only when they haven't been already
+    pub fn letter_probability(&self) -> LetterProbability {
+        if let Some(letters) = &self.letters {
+            return letters.clone();
+        }
+        let mut probability = LetterProbability::default();
+        for word in self.candidates.iter() {
+            probability.add(word);
+        }
+        probability
+    }
+
+    pub fn guessable_candidates(&self) -> impl Iterator<Item = (usize, &PackedWord)> {
+        self.candidates.iter().enumerate().filter(|(idx, _)| self.guessable[*idx])
+    }
+
+    // the guesses worth comparing, the most promising by letter coverage of the candidates and,
+    // when there is room, every candidate too
+    pub fn guess_pool(&self, limit: usize) -> Vec<PackedWord> {
+        let mut coverage = [0usize; ALPHABET];
+        for candidate in self.candidates.iter() {
+            for letter in candidate.letter_set().letters() {
+                coverage[letter as usize] += 1;
+            }
+        }
+        let score = |word: &PackedWord| -> usize {
+            word.letter_set().letters().map(|letter| coverage[letter as usize]).sum()
+        };
+
+        let mut pool: Vec<PackedWord> = Vec::new();
+        if self.candidates.len() <= limit {
+            pool.extend(self.guessable_candidates().map(|(_, word)| *word));
+        }
+        let mut ranked: Vec<(usize, &PackedWord)> = self.guesses.iter().map(|word| (score(word), word)).collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+        let mut seen: HashSet<PackedWord> = pool.iter().copied().collect();
+        for (_, word) in ranked {
+            if pool.len() >= limit.max(1) {
+                break;
+            }
+            if seen.insert(*word) {
+                pool.push(*word);
+            }
+        }
+        pool
+    }
+
+    // how many guesses may be compared without going over the budget
+    pub fn pool_limit(&self) -> usize {
+        (GUESS_BUDGET / self.candidates.len().max(1)).max(1)
+    }
+}
+
+pub trait Strategy {
+    // the next word to play, or nothing if no candidate can be suggested
+    fn guess(&self, position: &Position) -> Option<PackedWord>;
+
+    // the same as guess, reporting how many guesses have been looked at and giving back the best
+    // so far as soon as it is cancelled
+    fn guess_with(&self, position: &Position, _progress: &Progress) -> Option<PackedWord> {
+        self.guess(position)
+    }
+
+    // how good each of the guesses would be to play next, lower is better, by default only the
+    // strategy's own guess is better than the rest
+    fn scores(&self, position: &Position, guesses: &[PackedWord]) -> Vec<f64> {
+        let guess = self.guess(position);
+        guesses.iter().map(|word| if Some(word) == guess.as_ref() { 0.0 } else { 1.0 }).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrategyKind {
+    #[default]
+    LetterFrequency,
+    MostLikely,
+    MinExpectedGuesses,
+    Minimax,
+    Lookahead,
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 5] = [StrategyKind::LetterFrequency, StrategyKind::MostLikely, StrategyKind::MinExpectedGuesses, StrategyKind::Minimax, StrategyKind::Lookahead];
+
+    // the lookahead limits are only used by the lookahead strategy
+    pub fn strategy(&self, lookahead: &Lookahead) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::LetterFrequency => Box::new(LetterFrequency),
+            StrategyKind::MostLikely => Box::new(MostLikely),
+            StrategyKind::MinExpectedGuesses => Box::new(MinExpectedGuesses),
+            StrategyKind::Minimax => Box::new(Minimax),
+            StrategyKind::Lookahead => Box::new(*lookahead),
+        }
+    }
+
+    // the name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            StrategyKind::LetterFrequency => "frequency",
+            StrategyKind::MostLikely => "likely",
+            StrategyKind::MinExpectedGuesses => "expected",
+            StrategyKind::Minimax => "minimax",
+            StrategyKind::Lookahead => "lookahead",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<StrategyKind> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            StrategyKind::LetterFrequency => "Letter Frequency",
+            StrategyKind::MostLikely => "Most Likely",
+            StrategyKind::MinExpectedGuesses => "Fewest Guesses",
+            StrategyKind::Minimax => "Worst Case",
+            StrategyKind::Lookahead => "Lookahead",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+// the candidate whose letters are most common in their positions among the candidates
+pub struct LetterFrequency;
+
+impl Strategy for LetterFrequency {
+    fn guess(&self, position: &Position) -> Option<PackedWord> {
+        let probability = position.letter_probability();
+
+        let mut guessed_word = None;
+        let mut guessed_word_score = 0.0f64;
+        for (idx, word) in position.guessable_candidates() {
+            let score = probability.score_word(word) * position.weights[idx];
+            if score > guessed_word_score {
+                guessed_word_score = score;
+                guessed_word = Some(*word);
+            }
+        }
+        guessed_word
+    }
+
+    fn scores(&self, position: &Position, guesses: &[PackedWord]) -> Vec<f64> {
+        // scored the way guess picks, so only guessable candidates count and each by its weight
+        let probability = position.letter_probability();
+        let weights: HashMap<&PackedWord, f64> = position.guessable_candidates().map(|(idx, word)| (word, position.weights[idx])).collect();
+        guesses.iter().map(|word| -probability.score_word(word) * weights.get(word).copied().unwrap_or(0.0)).collect()
+    }
+}
+
+// the candidate most likely to be the answer, which maximizes the chance of winning this turn
+pub struct MostLikely;
+
+impl Strategy for MostLikely {
+    fn guess(&self, position: &Position) -> Option<PackedWord> {
+        let mut guessed_word: Option<(f64, &PackedWord)> = None;
+        for (idx, word) in position.guessable_candidates() {
+            if guessed_word.map_or(true, |(weight, _)| position.weights[idx] > weight) {
+                guessed_word = Some((position.weights[idx], word));
+            }
+        }
+        guessed_word.map(|(_, word)| *word)
+    }
+
+    fn scores(&self, position: &Position, guesses: &[PackedWord]) -> Vec<f64> {
+        let weights: HashMap<&PackedWord, f64> = position.candidates.iter().zip(position.weights.iter().copied()).collect();
+        guesses.iter().map(|word| -weights.get(word).copied().unwrap_or(0.0)).collect()
+    }
+}
+
+// the guess with the fewest expected guesses to finish, weighting each answer by how likely it is
+pub struct MinExpectedGuesses;
+
+impl MinExpectedGuesses {
+    // a rough number of guesses needed to find one of the given number of equally likely answers
+    pub fn remaining_guesses(candidates: usize) -> f64 {
+        if candidates <= 1 {
+            1.0
+        } else {
+            1.0 + (candidates as f64).log2() / 2.0
+        }
+    }
+
+    // the expected number of guesses to finish, counting this one
+    pub fn expected_guesses(position: &Position, guess: &PackedWord) -> f64 {
+        let total = position.total_weight();
+        let mut expected = 1.0;
+        for (pattern, bucket) in position.partition(guess) {
+            if pattern.is_solved() {
+                continue;
+            }
+            let weight: f64 = bucket.iter().map(|idx| position.weights[*idx]).sum();
+            expected += weight / total * Self::remaining_guesses(bucket.len());
+        }
+        expected
+    }
+}
+
+impl Strategy for MinExpectedGuesses {
+    fn guess(&self, position: &Position) -> Option<PackedWord> {
+        self.guess_with(position, &Progress::default())
+    }
+
+    fn guess_with(&self, position: &Position, progress: &Progress) -> Option<PackedWord> {
+        // with two or fewer left guessing the likeliest is always as good as anything else
+        if position.candidates.len() <= 2 {
+            return MostLikely.guess(position);
+        }
+        let pool = position.guess_pool(position.pool_limit());
+        progress.start(pool.len());
+        let mut guessed_word: Option<(f64, PackedWord)> = None;
+        for word in pool {
+            if guessed_word.is_some() && progress.is_cancelled() {
+                break;
+            }
+            let expected = Self::expected_guesses(position, &word);
+            if guessed_word.as_ref().map_or(true, |(best, _)| expected < *best) {
+                guessed_word = Some((expected, word));
+            }
+            progress.step();
+        }
+        guessed_word.map(|(_, word)| word)
+    }
+
+    fn scores(&self, position: &Position, guesses: &[PackedWord]) -> Vec<f64> {
+        guesses.iter().map(|word| Self::expected_guesses(position, word)).collect()
+    }
+}
+
+#[cfg(test)]
+mod strategy_tests {
+    use crate::wordle::test_word as word;
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<PackedWord> {
+        words.iter().map(|word| self::word(word)).collect()
+    }
+
+    #[test]
+    fn default() {
+        let result: Position = Default::default();
+        assert_eq!(result.attempts_left, MAX_ATTEMPTS);
+        assert_eq!(StrategyKind::default(), StrategyKind::LetterFrequency);
+    }
+
+    #[test]
+    fn parse() {
+        for kind in StrategyKind::ALL {
+            assert_eq!(StrategyKind::parse(kind.name()), Some(kind));
+        }
+        assert_eq!(StrategyKind::parse("psychic"), None);
+    }
+
+    #[test]
+    fn narrow() {
+        let position = Position::uniform(&["crane", "slate", "plate"]);
+        let result = position.narrow(&[0, 2]);
+        assert_eq!(result.candidates, words(&["crane", "plate"]));
+        assert_eq!(result.guesses.len(), 3);
+        assert_eq!(result.attempts_left, MAX_ATTEMPTS - 1);
+    }
+
+    #[test]
+    fn guess_pool() {
+        let mut position = Position::uniform(&["crane", "slate", "plate"]);
+        position.guesses.push(word("fuzzy"));
+        assert_eq!(position.guess_pool(10), words(&["crane", "slate", "plate", "fuzzy"]));
+        assert_eq!(position.guess_pool(1), words(&["plate"]));
+    }
+
+    #[test]
+    fn letter_frequency() {
+        let position = Position::uniform(&["abcde", "abcdf", "fghie"]);
+        assert_eq!(LetterFrequency.guess(&position), PackedWord::new("abcde"));
+
+        let mut position = position.clone();
+        position.guessable[0] = false;
+        assert_eq!(LetterFrequency.guess(&position), PackedWord::new("abcdf"));
+        assert_eq!(LetterFrequency.guess(&Position::default()), None);
+
+        // the word guess picks scores best, words it can't pick score nothing
+        let scores = LetterFrequency.scores(&position, &words(&["abcde", "abcdf", "fghie", "fuzzy"]));
+        assert_eq!(scores[0], 0.0);
+        assert!(scores[1] < scores[2]);
+        assert_eq!(scores[3], 0.0);
+
+        position.guessable[0] = true;
+        position.weights = vec![1.0, 1.0, 10.0];
+        assert_eq!(LetterFrequency.guess(&position), PackedWord::new("fghie"));
+        let scores = LetterFrequency.scores(&position, &words(&["abcde", "abcdf", "fghie"]));
+        assert!(scores[2] < scores[0] && scores[2] < scores[1]);
+    }
+
+    #[test]
+    fn most_likely() {
+        let mut position = Position::uniform(&["house", "xylyl", "zappy"]);
+        assert_eq!(MostLikely.guess(&position), PackedWord::new("house"));
+        position.weights = vec![1.0, 5.0, 2.0];
+        assert_eq!(MostLikely.guess(&position), PackedWord::new("xylyl"));
+        assert_eq!(MostLikely.scores(&position, &words(&["xylyl", "fuzzy"])), vec![-5.0, 0.0]);
+    }
+
+    #[test]
+    fn min_expected_guesses() {
+        // every candidate differs only in the first letter, so a word that tests several
+        // of those letters at once beats playing the candidates one at a time
+        let mut position = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        position.guesses.push(word("fbhkm"));
+        assert_eq!(MinExpectedGuesses.guess(&position), PackedWord::new("fbhkm"));
+        assert!(MinExpectedGuesses::expected_guesses(&position, &word("fbhkm")) < MinExpectedGuesses::expected_guesses(&position, &word("bills")));
+
+        // a single candidate is always the guess
+        let position = Position::uniform(&["bills"]);
+        assert_eq!(MinExpectedGuesses.guess(&position), PackedWord::new("bills"));
+    }
+
+    #[test]
+    fn cancelled() {
+        let mut position = Position::uniform(&["bills", "fills", "hills", "kills", "mills"]);
+        position.guesses.push(word("fbhkm"));
+        let progress = Progress::default();
+        progress.cancel();
+        // the first guess looked at is the best so far
+        let first = position.guess_pool(position.pool_limit())[0];
+        assert_eq!(MinExpectedGuesses.guess_with(&position, &progress), Some(first));
+        assert_eq!(progress.fraction(), 1.0 / 6.0);
+
+        let progress = Progress::default();
+        assert_eq!(MinExpectedGuesses.guess_with(&position, &progress), PackedWord::new("fbhkm"));
+        assert_eq!(progress.fraction(), 1.0);
+    }
+}
@@ -1,8 +1,8 @@
-use super::{Letter, LetterState};
+use super::{Letter, LetterState, PackedWord, Pattern};
 
 pub const MAX_LETTERS: usize = 5;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Word {
   pub letters: Vec<Letter>,
 }
@@ -16,46 +16,45 @@ impl Default for Word {
 }
 
 impl Word {
-    pub fn filter(self: &Self, str: &String) -> bool {
-        for (letter_idx, letter) in self.letters.iter().enumerate() {
-            match letter.get_state() {
-                LetterState::Disabled => {
-                    panic!("Got Disabled Letter State for letter in word that shouldn't be disabled?!?");
-                },
-                LetterState::Present => {
-                    // if the string doesn't contain the letter, then it's not a match
-                    if !str.contains(&letter.value.to_string()) {
-                        return true;
-                    }
-                    // if the letter in this exact index is the same, then it's not a match
-                    if str.chars().nth(letter_idx).unwrap() == letter.value {
-                        return true;
-                    }
-                },
-                LetterState::Incorrect => {
-                    if str.chars().nth(letter_idx).unwrap() == letter.value {
-                        return true;
-                    }
-
-                    for( idx, c) in str.chars().enumerate() {
-                        if c == letter.value {
-                            if self.letters[idx].get_state() != LetterState::Correct {
-                                return true;
-                            }
-                        }
-                    }
-                },
-                LetterState::Correct => {
-                    if str.chars().nth(letter_idx).unwrap() != letter.value {
-                        return true;
-                    }
-                },
-            }
+    // whether the word has to be filtered out, which is when playing this row's letters against it
+    // wouldn't give this row's colours
+    #[allow(clippy::needless_arbitrary_self_type)]
+    pub fn filter(self: &Self, str: &str) -> bool {
+        if self.letters.iter().any(|letter| letter.get_state() == LetterState::Disabled) {
+            panic!("Got Disabled Letter State for letter in word that shouldn't be disabled?!?");
         }
-        false
+        let guess = PackedWord::from_chars(self.letters.iter().map(|letter| letter.value));
+        let pattern = match (guess, PackedWord::new(str)) {
+            (Some(guess), Some(answer)) => Pattern::packed(&guess, &answer),
+            _ => Pattern::new(&self.value(), str),
+        };
+        pattern != self.pattern()
     }
 
-    pub fn set(self: &mut Self, word: &String) {
+    // the row the game shows for the guess when it gets the given feedback
+    pub fn feedback(guess: &str, pattern: Pattern) -> Word {
+        let mut word = Word::default();
+        word.set(guess);
+        for (letter, state) in word.letters.iter_mut().zip(pattern.states()) {
+            letter.set_state(state);
+        }
+        word
+    }
+
+    pub fn value(&self) -> String {
+        self.letters.iter().map(|letter| letter.value).collect()
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        let mut states: [LetterState; MAX_LETTERS] = Default::default();
+        for (state, letter) in states.iter_mut().zip(self.letters.iter()) {
+            *state = letter.get_state();
+        }
+        Pattern::from_states(&states)
+    }
+
+    #[allow(clippy::needless_arbitrary_self_type)]
+    pub fn set(self: &mut Self, word: &str) {
         if word.len() > MAX_LETTERS {
             panic!("Word {} of length {} is greater than allowed length {}", word, word.len(), MAX_LETTERS)
         }
@@ -97,6 +96,7 @@ mod word_tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn filter() {
 
         let mut word = get_default_word().clone();
@@ -119,6 +119,20 @@ mod word_tests {
         assert_eq!(word.filter(&str), true);
     }
 
+    #[test]
+    fn filter_repeated_letters() {
+        // the second e is grey because the answer only has one, checking letter by letter took the grey
+        // e to mean there is no e anywhere and ruled the answer out
+        let mut word: Word = Default::default();
+        word.set("speed");
+        word.letters[2].set_state(LetterState::Present);
+        word.letters[4].set_state(LetterState::Present);
+        assert!(!word.filter("abide"));
+        assert!(word.filter("eerie"));
+        assert_eq!(word.value(), "speed");
+        assert_eq!(Word::feedback("speed", Pattern::new("speed", "abide")), word);
+    }
+
     #[test]
     #[should_panic]
     fn filter_panic() {